@@ -0,0 +1,81 @@
+//! Compares the PNG round-trip of the fallback capture path with the raw frame buffer
+//! conversion, on a synthetic 4K frame.
+//!
+//! Run with `cargo bench -p desktop_dye_api --bench capture_pixels`.
+
+mod common;
+
+use std::time::Duration;
+
+use desktop_dye_api::functions::{
+    convert_raw_pixels, decode_png_pixels, RawPixelFormat, RawPixels,
+};
+
+use common::{bench, synthetic_pixels};
+
+const WIDTH: u32 = 3840;
+const HEIGHT: u32 = 2160;
+const ITERATIONS: u32 = 10;
+
+fn main() {
+    let bgra = synthetic_pixels(WIDTH, HEIGHT)
+        .iter()
+        .flat_map(|pixel| [pixel.blue(), pixel.green(), pixel.red(), u8::MAX])
+        .collect::<Vec<_>>();
+    let raw = RawPixels {
+        bytes: &bgra,
+        width: WIDTH,
+        height: HEIGHT,
+        stride: WIDTH as usize * 4,
+        format: RawPixelFormat::Bgra,
+    };
+
+    println!(
+        "Capturing a {}x{} frame ({} iterations)",
+        WIDTH, HEIGHT, ITERATIONS
+    );
+
+    // This mirrors what `screenshots` does for every capture (convert the BGRA buffer to RGBA
+    // and encode it as a PNG), followed by decoding the PNG again.
+    let duration = bench(ITERATIONS, || {
+        let png = encode_png(&bgra, WIDTH, HEIGHT);
+        decode_png_pixels(&png).unwrap()
+    });
+    print_duration("PNG round-trip (fallback)", duration);
+
+    for downsample_factor in [1, 2, 4] {
+        let duration = bench(ITERATIONS, || {
+            convert_raw_pixels(&raw, downsample_factor).unwrap()
+        });
+        print_duration(
+            &format!("Raw conversion, downsampled {}x", downsample_factor),
+            duration,
+        );
+    }
+}
+
+fn print_duration(name: &str, duration: Duration) {
+    println!(
+        "  {:<32} {:>10.2} ms/capture",
+        name,
+        duration.as_secs_f64() * 1000.0
+    );
+}
+
+fn encode_png(bgra: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let rgba = bgra
+        .chunks_exact(4)
+        .flat_map(|pixel| [pixel[2], pixel[1], pixel[0], pixel[3]])
+        .collect::<Vec<_>>();
+
+    let mut buffer = Vec::new();
+    let mut encoder = png::Encoder::new(&mut buffer, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header().unwrap();
+    writer.write_image_data(&rgba).unwrap();
+    writer.finish().unwrap();
+
+    buffer
+}
@@ -0,0 +1,39 @@
+use std::time::{Duration, Instant};
+
+use desktop_dye_api::models::colors::RgbColor;
+
+/// Returns the average duration of `iterations` runs of `f`.
+pub fn bench<T>(iterations: u32, mut f: impl FnMut() -> T) -> Duration {
+    // Warm up, so allocations of the first run don't skew the results.
+    std::hint::black_box(f());
+
+    let mut total = Duration::ZERO;
+    for _ in 0..iterations {
+        let start = Instant::now();
+        std::hint::black_box(f());
+        total += start.elapsed();
+    }
+
+    total / iterations
+}
+
+/// Creates a frame with gradients and noise, so nearly every pixel has a different color and
+/// it compresses roughly like a real screen.
+pub fn synthetic_pixels(width: u32, height: u32) -> Vec<RgbColor> {
+    let mut seed = 0x2545_f491_u32;
+    (0..width * height)
+        .map(|i| {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            let noise = (seed % 32) as u8;
+
+            let (x, y) = (i % width, i / width);
+            RgbColor::new(
+                (x * 255 / width) as u8 ^ noise,
+                (y * 255 / height) as u8 ^ (noise >> 1),
+                ((x + y) % 256) as u8,
+            )
+        })
+        .collect()
+}
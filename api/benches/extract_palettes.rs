@@ -0,0 +1,45 @@
+//! Compares how long every built-in algorithm takes to select the dominant colors of a
+//! synthetic frame with many different colors, relative to the default `color_thief`.
+//!
+//! Run with `cargo bench -p desktop_dye_api --bench extract_palettes`.
+
+mod common;
+
+use desktop_dye_api::{
+    config::{HueHistogramSettings, KMeansSettings},
+    functions::PaletteExtractorRegistry,
+};
+
+use common::{bench, synthetic_pixels};
+
+const WIDTH: u32 = 640;
+const HEIGHT: u32 = 360;
+const SAMPLE_SIZE: u8 = 3;
+const ITERATIONS: u32 = 5;
+
+fn main() {
+    let pixels = synthetic_pixels(WIDTH, HEIGHT);
+    let registry = PaletteExtractorRegistry::with_builtin_extractors(
+        &KMeansSettings::default(),
+        &HueHistogramSettings::default(),
+    );
+
+    println!(
+        "Selecting {} colors from a {}x{} frame ({} iterations)",
+        SAMPLE_SIZE, WIDTH, HEIGHT, ITERATIONS
+    );
+
+    let baseline = bench(ITERATIONS, || {
+        registry.extract("color_thief", &pixels, SAMPLE_SIZE)
+    });
+    for name in registry.names() {
+        let duration = bench(ITERATIONS, || registry.extract(name, &pixels, SAMPLE_SIZE));
+
+        println!(
+            "  {:<16} {:>10.2} ms/frame {:>8.1}x color_thief",
+            name,
+            duration.as_secs_f64() * 1000.0,
+            duration.as_secs_f64() / baseline.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use serde::Deserialize;
+
+/// A single step of the color grading pipeline, which is applied to every dominant color.
+///
+/// In the config file, every step is a map with a `step` field naming the kind of step, along
+/// with the fields of that step (e.g. `{ step: saturation, offset: 0.2 }`).
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(tag = "step")]
+pub enum ColorGradingStep {
+    /// Multiplies the saturation by `gain`, then adds `offset`.
+    #[serde(rename = "saturation")]
+    Saturation {
+        #[serde(default = "default_gain")]
+        gain: f64,
+        #[serde(default)]
+        offset: f64,
+    },
+    /// Multiplies the brightness by `gain`, then adds `offset`.
+    #[serde(rename = "value")]
+    Value {
+        #[serde(default = "default_gain")]
+        gain: f64,
+        #[serde(default)]
+        offset: f64,
+    },
+    /// Raises the brightness to the power of `1 / gamma`, so values above 1 brighten dark colors
+    /// and values below 1 darken them.
+    #[serde(rename = "gamma")]
+    Gamma {
+        #[serde(default = "default_gain")]
+        gamma: f64,
+    },
+    /// Scales the distance of the brightness to 0.5 by `amount`, so values above 1 increase the
+    /// contrast and values below 1 decrease it.
+    #[serde(rename = "contrast")]
+    Contrast {
+        #[serde(default = "default_gain")]
+        amount: f64,
+    },
+    /// Keeps the brightness between `min` and `max`.
+    #[serde(rename = "brightness_clamp")]
+    BrightnessClamp {
+        #[serde(default)]
+        min: f64,
+        #[serde(default = "default_max_brightness")]
+        max: f64,
+    },
+    /// Multiplies the red, green and blue channels by the given gains.
+    #[serde(rename = "white_balance")]
+    WhiteBalance {
+        #[serde(default = "default_gain")]
+        red: f64,
+        #[serde(default = "default_gain")]
+        green: f64,
+        #[serde(default = "default_gain")]
+        blue: f64,
+    },
+}
+
+fn default_gain() -> f64 {
+    1.0
+}
+
+fn default_max_brightness() -> f64 {
+    1.0
+}
+
+/// The color grading pipeline used when none is configured, which makes every color slightly
+/// more saturated and brighter.
+pub fn default_color_grading() -> Vec<ColorGradingStep> {
+    vec![
+        ColorGradingStep::Saturation {
+            gain: 1.0,
+            offset: 0.2,
+        },
+        ColorGradingStep::Value {
+            gain: 1.0,
+            offset: 0.2,
+        },
+    ]
+}
+
+impl ColorGradingStep {
+    /// Validates this step and returns a list of errors.
+    pub fn validate(&self, name: &str) -> Vec<String> {
+        let mut errors = Vec::new();
+        let mut validate_gain = |field: &str, gain: f64| {
+            if !gain.is_finite() || gain < 0.0 {
+                errors.push(format!(
+                    "{} {} must be 0 or greater. Found {}",
+                    name, field, gain
+                ));
+            }
+        };
+
+        match self {
+            ColorGradingStep::Saturation { gain, offset }
+            | ColorGradingStep::Value { gain, offset } => {
+                validate_gain("gain", *gain);
+                if !offset.is_finite() {
+                    errors.push(format!(
+                        "{} offset must be a finite number. Found {}",
+                        name, offset
+                    ));
+                }
+            }
+            ColorGradingStep::Contrast { amount } => validate_gain("amount", *amount),
+            ColorGradingStep::WhiteBalance { red, green, blue } => {
+                validate_gain("red", *red);
+                validate_gain("green", *green);
+                validate_gain("blue", *blue);
+            }
+            ColorGradingStep::Gamma { gamma } => {
+                if !gamma.is_finite() || *gamma <= 0.0 {
+                    errors.push(format!(
+                        "{} gamma must be greater than 0. Found {}",
+                        name, gamma
+                    ));
+                }
+            }
+            ColorGradingStep::BrightnessClamp { min, max } => {
+                if !(0.0..=1.0).contains(min) || !(0.0..=1.0).contains(max) {
+                    errors.push(format!(
+                        "{} min and max must be between 0 and 1. Found {} and {}",
+                        name, min, max
+                    ));
+                } else if min > max {
+                    errors.push(format!(
+                        "{} min must not be greater than max. Found {} and {}",
+                        name, min, max
+                    ));
+                }
+            }
+        }
+
+        errors
+    }
+}
@@ -0,0 +1,58 @@
+use serde::Deserialize;
+
+pub const DEFAULT_DEDUPLICATION_MIN_DISTANCE: f64 = 5.0;
+pub const DEFAULT_DEDUPLICATION_BACKFILL: bool = true;
+
+/// How many times the sample size of candidate colors are calculated when back-filling.
+pub const DEDUPLICATION_CANDIDATE_FACTOR: u8 = 2;
+
+/// Merges dominant colors that look (almost) identical.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct Deduplication {
+    /// The CIEDE2000 distance colors must be apart to be considered distinct.
+    #[serde(default = "default_min_distance")]
+    pub min_distance: f64,
+    /// Whether to replace merged colors with the next most dominant distinct colors.
+    #[serde(default = "default_backfill")]
+    pub backfill: bool,
+}
+
+fn default_min_distance() -> f64 {
+    DEFAULT_DEDUPLICATION_MIN_DISTANCE
+}
+
+fn default_backfill() -> bool {
+    DEFAULT_DEDUPLICATION_BACKFILL
+}
+
+impl Default for Deduplication {
+    fn default() -> Self {
+        Self {
+            min_distance: DEFAULT_DEDUPLICATION_MIN_DISTANCE,
+            backfill: DEFAULT_DEDUPLICATION_BACKFILL,
+        }
+    }
+}
+
+impl Deduplication {
+    /// Returns the amount of candidate colors to calculate for the given sample size.
+    pub fn candidate_count(&self, sample_size: u8) -> u8 {
+        if self.backfill {
+            sample_size.saturating_mul(DEDUPLICATION_CANDIDATE_FACTOR)
+        } else {
+            sample_size
+        }
+    }
+
+    /// Validates this deduplication and returns a list of errors.
+    pub fn validate(&self) -> Vec<String> {
+        if !self.min_distance.is_finite() || self.min_distance < 0.0 {
+            return vec![format!(
+                "Deduplication min distance must be 0 or greater. Found {}",
+                self.min_distance
+            )];
+        }
+
+        vec![]
+    }
+}
@@ -0,0 +1,67 @@
+use serde::Deserialize;
+use std::fmt::{Display, Formatter};
+
+pub const DEFAULT_DOWNSAMPLING_METHOD: DownsamplingMethod = DownsamplingMethod::Box;
+
+/// Reduces the amount of pixels of a frame before its dominant colors are calculated.
+///
+/// Frames are shrunk by the smallest whole factor that brings them within `max_pixels`.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct Downsampling {
+    #[serde(default = "default_method")]
+    pub method: DownsamplingMethod,
+    pub max_pixels: u32,
+}
+
+fn default_method() -> DownsamplingMethod {
+    DEFAULT_DOWNSAMPLING_METHOD
+}
+
+impl Downsampling {
+    /// Returns the factor to shrink a frame of the given size by to fit within `max_pixels`, or
+    /// 1 if the frame fits already.
+    pub fn factor(&self, frame_width: u32, frame_height: u32) -> u32 {
+        let max_pixels = self.max_pixels.max(1) as u64;
+        let pixel_count = |factor: u32| {
+            frame_width.div_ceil(factor) as u64 * frame_height.div_ceil(factor) as u64
+        };
+
+        let mut factor = ((frame_width as u64 * frame_height as u64) as f64 / max_pixels as f64)
+            .sqrt()
+            .floor()
+            .max(1.0) as u32;
+        while pixel_count(factor) > max_pixels {
+            factor += 1;
+        }
+
+        factor
+    }
+
+    /// Validates this downsampling and returns a list of errors.
+    pub fn validate(&self) -> Vec<String> {
+        if self.max_pixels == 0 {
+            return vec!["Downsampling max pixels must be greater than 0".to_string()];
+        }
+
+        vec![]
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub enum DownsamplingMethod {
+    /// Keeps only every n-th pixel of every n-th row.
+    #[serde(rename = "stride")]
+    Stride,
+    /// Averages every block of n by n pixels.
+    #[serde(rename = "box")]
+    Box,
+}
+
+impl Display for DownsamplingMethod {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DownsamplingMethod::Stride => write!(f, "Stride"),
+            DownsamplingMethod::Box => write!(f, "Box"),
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use serde::Deserialize;
+
+use crate::{config::Dimension, models::frame::Rect};
+
+pub const DEFAULT_EDGE_ZONE_DEPTH: Dimension = Dimension::Percentage(10.0);
+pub const DEFAULT_EDGE_ZONE_SEGMENTS: u8 = 1;
+
+/// Splits the border of a frame into zones, each of which gets its own color.
+///
+/// Every side of the frame is split into the given amount of segments (where 0 disables the
+/// side), each extending `depth` pixels into the frame.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct EdgeZones {
+    #[serde(default = "default_depth")]
+    pub depth: Dimension,
+    #[serde(default = "default_segments")]
+    pub top: u8,
+    #[serde(default = "default_segments")]
+    pub right: u8,
+    #[serde(default = "default_segments")]
+    pub bottom: u8,
+    #[serde(default = "default_segments")]
+    pub left: u8,
+}
+
+fn default_depth() -> Dimension {
+    DEFAULT_EDGE_ZONE_DEPTH
+}
+
+fn default_segments() -> u8 {
+    DEFAULT_EDGE_ZONE_SEGMENTS
+}
+
+impl EdgeZones {
+    /// The total amount of zones.
+    pub fn count(&self) -> usize {
+        [self.top, self.right, self.bottom, self.left]
+            .iter()
+            .map(|segments| *segments as usize)
+            .sum()
+    }
+
+    /// Returns the rectangle of every zone for a frame of the given size.
+    ///
+    /// The zones are ordered clockwise, starting at the top left corner: the top side from left
+    /// to right, the right side from top to bottom, the bottom side from right to left and the
+    /// left side from bottom to top.
+    ///
+    /// For an empty frame, every zone is an empty rectangle.
+    pub fn resolve(&self, frame_width: u32, frame_height: u32) -> Vec<Rect> {
+        resolve_edge_rects(
+            frame_width,
+            frame_height,
+            &self.depth,
+            [self.top, self.right, self.bottom, self.left].map(|segments| segments as u32),
+        )
+    }
+
+    /// Validates these edge zones and returns a list of errors.
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        if let Some(depth_error) = validate_edge_depth(&self.depth, "Edge zone depth") {
+            errors.push(depth_error);
+        }
+
+        if self.count() == 0 {
+            errors.push("Edge zones must contain at least one zone".to_string());
+        }
+
+        errors
+    }
+}
+
+/// Splits every side of a frame of the given size into the given amount of segments
+/// (ordered top, right, bottom, left), each reaching `depth` into the frame.
+///
+/// The rectangles are ordered clockwise, starting at the top left corner.
+pub(crate) fn resolve_edge_rects(
+    frame_width: u32,
+    frame_height: u32,
+    depth: &Dimension,
+    [top, right, bottom, left]: [u32; 4],
+) -> Vec<Rect> {
+    // A side is at least 1 pixel deep, unless the frame is empty along that axis (e.g. after
+    // cropping it to an empty capture region), in which case its zones are empty.
+    let horizontal_depth = depth.resolve(frame_height).max(1).min(frame_height);
+    let vertical_depth = depth.resolve(frame_width).max(1).min(frame_width);
+
+    let top = split(frame_width, top)
+        .map(|(start, length)| Rect::new(start, 0, length, horizontal_depth))
+        .collect::<Vec<_>>();
+    let right = split(frame_height, right)
+        .map(|(start, length)| {
+            Rect::new(frame_width - vertical_depth, start, vertical_depth, length)
+        })
+        .collect::<Vec<_>>();
+    let bottom = split(frame_width, bottom)
+        .rev()
+        .map(|(start, length)| {
+            Rect::new(
+                start,
+                frame_height - horizontal_depth,
+                length,
+                horizontal_depth,
+            )
+        })
+        .collect::<Vec<_>>();
+    let left = split(frame_height, left)
+        .rev()
+        .map(|(start, length)| Rect::new(0, start, vertical_depth, length))
+        .collect::<Vec<_>>();
+
+    [top, right, bottom, left].concat()
+}
+
+/// Validates the depth of an edge area, returning an error if it is invalid.
+pub(crate) fn validate_edge_depth(depth: &Dimension, name: &str) -> Option<String> {
+    match depth {
+        Dimension::Pixels(0) => Some(format!("{} must be greater than 0", name)),
+        Dimension::Percentage(percentage) if *percentage <= 0.0 || *percentage > 50.0 => {
+            Some(format!(
+                "{} must be a percentage greater than 0% and at most 50%. Found {}",
+                name, depth
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Splits a side of the given length into the given amount of segments of (nearly) equal
+/// length, returning the start and length of each segment.
+fn split(length: u32, segments: u32) -> impl DoubleEndedIterator<Item = (u32, u32)> {
+    let segments = segments as u64;
+
+    (0..segments).map(move |i| {
+        let start = (i * length as u64 / segments) as u32;
+        let end = ((i + 1) * length as u64 / segments) as u32;
+
+        (start, end - start)
+    })
+}
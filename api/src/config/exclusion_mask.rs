@@ -0,0 +1,84 @@
+use anyhow::*;
+use serde::Deserialize;
+use std::path::PathBuf;
+
+use crate::{
+    functions::{decode_png_pixels, u8_to_f64},
+    models::{colors::RgbColor, frame::Frame},
+};
+
+/// The gray level (from 0 to 1) below which a pixel in an exclusion mask is considered black.
+const MASK_GRAY_THRESHOLD: f64 = 0.5;
+
+/// A PNG image marking which pixels of a frame to ignore, where black pixels are ignored.
+///
+/// Masks are read as grayscale images: images of any PNG color type and bit depth are accepted,
+/// and color pixels are reduced to their gray level (luma). Fully transparent pixels are ignored
+/// as well.
+///
+/// The image is loaded when the config is read. When applied to a frame of a different size,
+/// the mask is scaled to fit the frame.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(try_from = "PathBuf")]
+pub struct ExclusionMask {
+    pub path: PathBuf,
+    width: u32,
+    height: u32,
+    excluded: Vec<bool>,
+}
+
+impl TryFrom<PathBuf> for ExclusionMask {
+    type Error = anyhow::Error;
+
+    fn try_from(path: PathBuf) -> Result<Self> {
+        let buffer = std::fs::read(&path)
+            .context(format!("Failed to read exclusion mask {}", path.display()))?;
+        let image = decode_png_pixels(&buffer).context(format!(
+            "Failed to decode exclusion mask {}",
+            path.display()
+        ))?;
+
+        Ok(Self::from_frame(path, &image))
+    }
+}
+
+impl ExclusionMask {
+    fn from_frame(path: PathBuf, image: &Frame) -> Self {
+        let excluded = image
+            .pixels
+            .iter()
+            .enumerate()
+            .map(|(index, pixel)| {
+                image.is_excluded(index) || gray_level(pixel) < MASK_GRAY_THRESHOLD
+            })
+            .collect();
+
+        Self {
+            path,
+            width: image.width,
+            height: image.height,
+            excluded,
+        }
+    }
+
+    /// Marks the pixels of the given frame that are black in this mask as excluded.
+    pub fn apply(&self, frame: &mut Frame) {
+        let (mask_width, mask_height) = (self.width as u64, self.height as u64);
+        let (frame_width, frame_height) = (frame.width as u64, frame.height as u64);
+
+        frame.exclude_where(|x, y| {
+            let mask_x = x as u64 * mask_width / frame_width;
+            let mask_y = y as u64 * mask_height / frame_height;
+
+            self.excluded[(mask_y * mask_width + mask_x) as usize]
+        });
+    }
+}
+
+/// Returns the gray level of the given pixel (from 0 to 1), using the Rec. 709 luma weights.
+/// Gray pixels keep their level.
+fn gray_level(pixel: &RgbColor) -> f64 {
+    0.2126 * u8_to_f64(pixel.red())
+        + 0.7152 * u8_to_f64(pixel.green())
+        + 0.0722 * u8_to_f64(pixel.blue())
+}
@@ -0,0 +1,77 @@
+use serde::Deserialize;
+use std::fmt::{Display, Formatter};
+
+pub const DEFAULT_GRADIENT_COUNT: u8 = 8;
+pub const DEFAULT_GRADIENT_COLOR_SPACE: GradientColorSpace = GradientColorSpace::Oklab;
+
+pub const GRADIENT_COUNT_RANGE: std::ops::RangeInclusive<u8> = 2..=100;
+
+/// Settings for the `gradient` color selection mode.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct GradientSettings {
+    /// The amount of colors in the gradient, independent of the amount of dominant colors.
+    #[serde(default = "default_count")]
+    pub count: u8,
+    /// The color space the dominant colors are interpolated in.
+    #[serde(default = "default_color_space")]
+    pub color_space: GradientColorSpace,
+}
+
+fn default_count() -> u8 {
+    DEFAULT_GRADIENT_COUNT
+}
+
+fn default_color_space() -> GradientColorSpace {
+    DEFAULT_GRADIENT_COLOR_SPACE
+}
+
+impl Default for GradientSettings {
+    fn default() -> Self {
+        Self {
+            count: DEFAULT_GRADIENT_COUNT,
+            color_space: DEFAULT_GRADIENT_COLOR_SPACE,
+        }
+    }
+}
+
+impl GradientSettings {
+    /// Validates these settings and returns a list of errors.
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        if !GRADIENT_COUNT_RANGE.contains(&self.count) {
+            errors.push(format!(
+                "Gradient count must be between {} and {}. Found {}",
+                GRADIENT_COUNT_RANGE.start(),
+                GRADIENT_COUNT_RANGE.end(),
+                self.count
+            ));
+        }
+
+        errors
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub enum GradientColorSpace {
+    /// Interpolates in OKLab, where the colors between two colors look evenly spaced.
+    #[serde(rename = "oklab")]
+    Oklab,
+    /// Interpolates the lightness, chroma and hue in OKLCH, which keeps the colors between two
+    /// vivid colors vivid, instead of passing through grayer colors.
+    #[serde(rename = "oklch")]
+    Oklch,
+    /// Interpolates the red, green and blue channels, as most light strips do on their own.
+    #[serde(rename = "rgb")]
+    Rgb,
+}
+
+impl Display for GradientColorSpace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GradientColorSpace::Oklab => write!(f, "OKLab"),
+            GradientColorSpace::Oklch => write!(f, "OKLCH"),
+            GradientColorSpace::Rgb => write!(f, "RGB"),
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use serde::Deserialize;
+
+pub const DEFAULT_HUE_HISTOGRAM_BINS: u16 = 36;
+pub const DEFAULT_HUE_HISTOGRAM_MIN_SATURATION: f64 = 0.15;
+pub const DEFAULT_HUE_HISTOGRAM_MIN_VALUE: f64 = 0.15;
+
+/// Settings for the `hue_histogram` algorithm.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct HueHistogramSettings {
+    /// The amount of equally sized hue ranges the color wheel is divided into.
+    #[serde(default = "default_bins")]
+    pub bins: u16,
+    /// Pixels with a lower saturation (from 0 to 1) are ignored.
+    #[serde(default = "default_min_saturation")]
+    pub min_saturation: f64,
+    /// Pixels with a lower brightness (from 0 to 1) are ignored.
+    #[serde(default = "default_min_value")]
+    pub min_value: f64,
+}
+
+fn default_bins() -> u16 {
+    DEFAULT_HUE_HISTOGRAM_BINS
+}
+
+fn default_min_saturation() -> f64 {
+    DEFAULT_HUE_HISTOGRAM_MIN_SATURATION
+}
+
+fn default_min_value() -> f64 {
+    DEFAULT_HUE_HISTOGRAM_MIN_VALUE
+}
+
+impl Default for HueHistogramSettings {
+    fn default() -> Self {
+        Self {
+            bins: DEFAULT_HUE_HISTOGRAM_BINS,
+            min_saturation: DEFAULT_HUE_HISTOGRAM_MIN_SATURATION,
+            min_value: DEFAULT_HUE_HISTOGRAM_MIN_VALUE,
+        }
+    }
+}
+
+impl HueHistogramSettings {
+    /// Validates these settings and returns a list of errors.
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        if !(1..=360).contains(&self.bins) {
+            errors.push(format!(
+                "Hue histogram bins must be between 1 and 360. Found {}",
+                self.bins
+            ));
+        }
+
+        for (name, value) in [
+            ("min saturation", self.min_saturation),
+            ("min value", self.min_value),
+        ] {
+            if !(0.0..=1.0).contains(&value) {
+                errors.push(format!(
+                    "Hue histogram {} must be between 0 and 1. Found {}",
+                    name, value
+                ));
+            }
+        }
+
+        errors
+    }
+}
@@ -0,0 +1,62 @@
+use serde::Deserialize;
+
+pub const DEFAULT_KMEANS_SEED: u64 = 0;
+pub const DEFAULT_KMEANS_MAX_ITERATIONS: u32 = 100;
+pub const DEFAULT_KMEANS_CONVERGENCE_THRESHOLD: f64 = 0.1;
+
+/// Settings for the `kmeans_lab` algorithm.
+///
+/// The same pixels and seed always result in the same colors.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct KMeansSettings {
+    #[serde(default = "default_seed")]
+    pub seed: u64,
+    #[serde(default = "default_max_iterations")]
+    pub max_iterations: u32,
+    /// The distance (in CIELAB units) every cluster center must move less than for the
+    /// clustering to be considered converged.
+    #[serde(default = "default_convergence_threshold")]
+    pub convergence_threshold: f64,
+}
+
+fn default_seed() -> u64 {
+    DEFAULT_KMEANS_SEED
+}
+
+fn default_max_iterations() -> u32 {
+    DEFAULT_KMEANS_MAX_ITERATIONS
+}
+
+fn default_convergence_threshold() -> f64 {
+    DEFAULT_KMEANS_CONVERGENCE_THRESHOLD
+}
+
+impl Default for KMeansSettings {
+    fn default() -> Self {
+        Self {
+            seed: DEFAULT_KMEANS_SEED,
+            max_iterations: DEFAULT_KMEANS_MAX_ITERATIONS,
+            convergence_threshold: DEFAULT_KMEANS_CONVERGENCE_THRESHOLD,
+        }
+    }
+}
+
+impl KMeansSettings {
+    /// Validates these settings and returns a list of errors.
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        if self.max_iterations == 0 {
+            errors.push("K-means max iterations must be greater than 0".to_string());
+        }
+
+        if self.convergence_threshold < 0.0 {
+            errors.push(format!(
+                "K-means convergence threshold must not be negative. Found {}",
+                self.convergence_threshold
+            ));
+        }
+
+        errors
+    }
+}
@@ -0,0 +1,216 @@
+use serde::Deserialize;
+use std::fmt::{Display, Formatter};
+
+use crate::{
+    config::{resolve_edge_rects, validate_edge_depth, Dimension},
+    models::frame::Rect,
+};
+
+pub const DEFAULT_LED_DEPTH: Dimension = Dimension::Percentage(5.0);
+pub const DEFAULT_LED_START_CORNER: Corner = Corner::BottomLeft;
+pub const DEFAULT_LED_DIRECTION: Direction = Direction::Clockwise;
+
+/// Describes an addressable LED strip mounted around the border of a screen.
+///
+/// Every side has an amount of LED positions, each of which samples a box of the screen reaching
+/// `depth` into the screen. The strip starts at `start_corner` (moved `offset` positions along
+/// the strip) and runs in `direction`. Positions that fall within a gap have no LED.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct LedLayout {
+    #[serde(default)]
+    pub top: u32,
+    #[serde(default)]
+    pub right: u32,
+    #[serde(default)]
+    pub bottom: u32,
+    #[serde(default)]
+    pub left: u32,
+    #[serde(default = "default_start_corner")]
+    pub start_corner: Corner,
+    #[serde(default = "default_direction")]
+    pub direction: Direction,
+    #[serde(default)]
+    pub offset: u32,
+    #[serde(default)]
+    pub gaps: Vec<LedGap>,
+    #[serde(default = "default_depth")]
+    pub depth: Dimension,
+}
+
+/// A range of LED positions without an LED, counted along the strip from its first LED.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct LedGap {
+    pub start: u32,
+    pub length: u32,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub enum Corner {
+    #[serde(rename = "top_left")]
+    TopLeft,
+    #[serde(rename = "top_right")]
+    TopRight,
+    #[serde(rename = "bottom_right")]
+    BottomRight,
+    #[serde(rename = "bottom_left")]
+    BottomLeft,
+}
+
+impl Display for Corner {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Corner::TopLeft => write!(f, "Top Left"),
+            Corner::TopRight => write!(f, "Top Right"),
+            Corner::BottomRight => write!(f, "Bottom Right"),
+            Corner::BottomLeft => write!(f, "Bottom Left"),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub enum Direction {
+    #[serde(rename = "clockwise")]
+    Clockwise,
+    #[serde(rename = "counter_clockwise")]
+    CounterClockwise,
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Direction::Clockwise => write!(f, "Clockwise"),
+            Direction::CounterClockwise => write!(f, "Counter Clockwise"),
+        }
+    }
+}
+
+fn default_start_corner() -> Corner {
+    DEFAULT_LED_START_CORNER
+}
+
+fn default_direction() -> Direction {
+    DEFAULT_LED_DIRECTION
+}
+
+fn default_depth() -> Dimension {
+    DEFAULT_LED_DEPTH
+}
+
+impl LedLayout {
+    /// The total amount of LED positions around the screen, including those within gaps.
+    ///
+    /// Returns `None` if the amount does not fit in a `u32`.
+    pub fn position_count(&self) -> Option<u32> {
+        self.top
+            .checked_add(self.right)?
+            .checked_add(self.bottom)?
+            .checked_add(self.left)
+    }
+
+    /// The amount of LEDs on the strip, which excludes the positions within gaps.
+    pub fn led_count(&self) -> usize {
+        self.strip_positions().len()
+    }
+
+    /// Returns the sampling box of every LED for a frame of the given size, in the order of the
+    /// LEDs on the strip.
+    pub fn resolve(&self, frame_width: u32, frame_height: u32) -> Vec<Rect> {
+        let rects = resolve_edge_rects(
+            frame_width,
+            frame_height,
+            &self.depth,
+            [self.top, self.right, self.bottom, self.left],
+        );
+
+        self.strip_positions()
+            .into_iter()
+            .map(|position| rects[position])
+            .collect()
+    }
+
+    /// Returns, for every LED on the strip, the index of its position around the screen.
+    ///
+    /// Positions are numbered clockwise, starting at the top left corner (like edge zones).
+    fn strip_positions(&self) -> Vec<usize> {
+        // An invalid layout (with too many positions) has no LEDs.
+        let position_count = self.position_count().unwrap_or(0) as usize;
+        if position_count == 0 {
+            return vec![];
+        }
+
+        let corner = match self.start_corner {
+            Corner::TopLeft => 0,
+            Corner::TopRight => self.top,
+            Corner::BottomRight => self.top + self.right,
+            Corner::BottomLeft => self.top + self.right + self.bottom,
+        } as usize;
+
+        (0..position_count)
+            .filter(|strip_index| {
+                !self.gaps.iter().any(|gap| {
+                    let strip_index = *strip_index as u32;
+                    strip_index >= gap.start && strip_index < gap.start.saturating_add(gap.length)
+                })
+            })
+            .map(|strip_index| {
+                let steps = strip_index + self.offset as usize;
+                match self.direction {
+                    Direction::Clockwise => (corner + steps) % position_count,
+                    Direction::CounterClockwise => {
+                        (corner + position_count - 1 - steps % position_count) % position_count
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Validates this layout and returns a list of errors.
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        if let Some(depth_error) = validate_edge_depth(&self.depth, "LED sampling depth") {
+            errors.push(depth_error);
+        }
+
+        let position_count = match self.position_count() {
+            Some(position_count) => position_count,
+            None => {
+                errors.push(format!(
+                    "LED layout must contain at most {} LED positions. Found {} top, {} right, {} bottom and {} left",
+                    u32::MAX,
+                    self.top,
+                    self.right,
+                    self.bottom,
+                    self.left
+                ));
+                return errors;
+            }
+        };
+        if position_count == 0 {
+            errors.push("LED layout must contain at least one LED".to_string());
+        }
+
+        for (i, gap) in self.gaps.iter().enumerate() {
+            let ends_within_positions = gap
+                .start
+                .checked_add(gap.length)
+                .map(|end| end <= position_count)
+                .unwrap_or(false);
+            if gap.length == 0 || !ends_within_positions {
+                errors.push(format!(
+                    "LED gap {} must have a length greater than 0 and end within the {} LED positions. Found start {} and length {}",
+                    i + 1,
+                    position_count,
+                    gap.start,
+                    gap.length
+                ));
+            }
+        }
+
+        if position_count > 0 && self.led_count() == 0 {
+            errors.push("LED gaps must not cover every LED position".to_string());
+        }
+
+        errors
+    }
+}
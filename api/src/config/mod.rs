@@ -1,19 +1,60 @@
+mod color_grading;
+mod deduplication;
+mod downsampling;
+mod edge_zones;
+mod exclusion_mask;
+mod gradient;
+mod hue_histogram;
+mod kmeans;
+mod led_layout;
+mod region;
+mod screen_selection;
+mod smoothing;
+
+pub use color_grading::*;
+pub use deduplication::*;
+pub use downsampling::*;
+pub use edge_zones::*;
+pub use exclusion_mask::*;
+pub use gradient::*;
+pub use hue_histogram::*;
+pub use kmeans::*;
+pub use led_layout::*;
+pub use region::*;
+pub use screen_selection::*;
+pub use smoothing::*;
+
 use anyhow::*;
 use optional_struct::*;
+use screenshots::Screen;
 use serde::Deserialize;
 use std::{
+    collections::HashMap,
     fmt::{Display, Formatter},
     path::PathBuf,
 };
 
+use crate::functions::PaletteExtractorRegistry;
+
 pub const CONFIG_FILE_NAME: &str = "config.yaml";
 pub const DEFAULT_CONFIG_FILE_CONTENTS: &str = include_str!("../../assets/default_config.yaml");
 
+pub const DEFAULT_PIXEL_SOURCE: PixelSourceKind = PixelSourceKind::Screen;
+pub const DEFAULT_MULTI_SCREEN_MODE: MultiScreenMode = MultiScreenMode::Merge;
+pub const DEFAULT_DETECT_LETTERBOX: bool = false;
 pub const DEFAULT_SAMPLE_SIZE: u8 = 3;
 pub const DEFAULT_ALGORITHM: DominantColorAlgorithm = DominantColorAlgorithm::ColorThief;
 pub const DEFAULT_CAPTURE_INTERVAL: f64 = 3.0;
+pub const DEFAULT_CAPTURE_INTERVAL_MODE: CaptureIntervalMode = CaptureIntervalMode::Fixed;
+pub const DEFAULT_MIN_CAPTURE_INTERVAL: f64 = 0.5;
+pub const DEFAULT_MAX_CAPTURE_INTERVAL: f64 = 10.0;
+pub const DEFAULT_CAPTURE_INTERVAL_SENSITIVITY: f64 = 20.0;
+pub const DEFAULT_FRAME_CHANGE_THRESHOLD: f64 = 0.01;
+pub const DEFAULT_COLOR_CHANGE_THRESHOLD: f64 = 1.0;
 pub const DEFAULT_COLOR_SELECTION_MODE: ColorSelectionMode = ColorSelectionMode::Default;
 pub const DEFAULT_HUE_SHIFT: f64 = 45.0;
+pub const DEFAULT_ANALOGOUS_ANGLE: f64 = 30.0;
+pub const DEFAULT_SPLIT_COMPLEMENTARY_ANGLE: f64 = 30.0;
 pub const DEFAULT_COLOR_FORMAT: ColorFormat = ColorFormat::Rgbb;
 pub const DEFAULT_BRIGHTNESS_FACTOR: f64 = 1.0;
 
@@ -22,17 +63,43 @@ pub const SAMPLE_SIZE_RANGE: std::ops::RangeInclusive<u8> = 1..=10;
 #[optional_struct]
 #[derive(Debug, Deserialize)]
 pub struct DesktopDyeConfig {
-    pub screen_id: Option<u32>,
+    pub source: PixelSourceKind,
+    pub screen_id: Option<ScreenSelection>,
+    pub multi_screen_mode: MultiScreenMode,
+    pub screen_targets: HashMap<u32, String>,
+    pub image_path: Option<PathBuf>,
+    pub capture_region: Option<Region>,
+    pub exclusion_regions: Vec<Region>,
+    pub exclusion_mask: Option<ExclusionMask>,
+    pub detect_letterbox: bool,
+    pub edge_zones: Option<EdgeZones>,
+    pub led_layout: Option<LedLayout>,
+    pub downsampling: Option<Downsampling>,
     pub ha_endpoint: String,
     pub ha_token: String,
     pub ha_target_entity_id: String,
     pub sample_size: u8,
     pub algorithm: DominantColorAlgorithm,
+    pub deduplication: Option<Deduplication>,
+    pub kmeans: KMeansSettings,
+    pub hue_histogram: HueHistogramSettings,
     pub capture_interval: f64,
+    pub capture_interval_mode: CaptureIntervalMode,
+    pub min_capture_interval: f64,
+    pub max_capture_interval: f64,
+    pub capture_interval_sensitivity: f64,
+    pub frame_change_threshold: f64,
+    pub color_change_threshold: f64,
+    pub max_submission_age: Option<f64>,
     pub mode: ColorSelectionMode,
     pub hue_shift: f64,
+    pub analogous_angle: f64,
+    pub split_complementary_angle: f64,
+    pub gradient: GradientSettings,
     pub color_format: ColorFormat,
     pub brightness_factor: f64,
+    pub color_grading: Vec<ColorGradingStep>,
+    pub smoothing: Option<Smoothing>,
 }
 
 impl DesktopDyeConfig {
@@ -88,8 +155,16 @@ impl DesktopDyeConfig {
             return Err(anyhow!("Config file does not exist"));
         }
 
-        let file = std::fs::File::open(&path).context("Failed to open config file")?;
-        let optional_config = serde_yaml::from_reader::<_, OptionalDesktopDyeConfig>(file)
+        let contents = std::fs::read_to_string(&path).context("Failed to open config file")?;
+
+        Self::from_yaml(&contents)
+    }
+
+    /// Parses the given contents of a config file and validates them.
+    ///
+    /// If the config is invalid, an error listing every problem is returned.
+    pub fn from_yaml(contents: &str) -> Result<Self> {
+        let optional_config = serde_yaml::from_str::<OptionalDesktopDyeConfig>(contents)
             .context("Failed to read config file")?;
 
         let validation_errors = Self::validate_optional_config(&optional_config);
@@ -107,21 +182,69 @@ impl DesktopDyeConfig {
         }
 
         Ok(DesktopDyeConfig {
+            source: optional_config.source.unwrap_or(DEFAULT_PIXEL_SOURCE),
             screen_id: optional_config.screen_id,
+            multi_screen_mode: optional_config
+                .multi_screen_mode
+                .unwrap_or(DEFAULT_MULTI_SCREEN_MODE),
+            screen_targets: optional_config.screen_targets.unwrap_or_default(),
+            image_path: optional_config.image_path,
+            capture_region: optional_config.capture_region,
+            exclusion_regions: optional_config.exclusion_regions.unwrap_or_default(),
+            exclusion_mask: optional_config.exclusion_mask,
+            detect_letterbox: optional_config
+                .detect_letterbox
+                .unwrap_or(DEFAULT_DETECT_LETTERBOX),
+            edge_zones: optional_config.edge_zones,
+            led_layout: optional_config.led_layout,
+            downsampling: optional_config.downsampling,
             ha_endpoint: optional_config.ha_endpoint.unwrap(),
             ha_token: optional_config.ha_token.unwrap(),
             ha_target_entity_id: optional_config.ha_target_entity_id.unwrap(),
             sample_size: optional_config.sample_size.unwrap_or(DEFAULT_SAMPLE_SIZE),
             algorithm: optional_config.algorithm.unwrap_or(DEFAULT_ALGORITHM),
+            deduplication: optional_config.deduplication,
+            kmeans: optional_config.kmeans.unwrap_or_default(),
+            hue_histogram: optional_config.hue_histogram.unwrap_or_default(),
             capture_interval: optional_config
                 .capture_interval
                 .unwrap_or(DEFAULT_CAPTURE_INTERVAL),
+            capture_interval_mode: optional_config
+                .capture_interval_mode
+                .unwrap_or(DEFAULT_CAPTURE_INTERVAL_MODE),
+            min_capture_interval: optional_config
+                .min_capture_interval
+                .unwrap_or(DEFAULT_MIN_CAPTURE_INTERVAL),
+            max_capture_interval: optional_config
+                .max_capture_interval
+                .unwrap_or(DEFAULT_MAX_CAPTURE_INTERVAL),
+            capture_interval_sensitivity: optional_config
+                .capture_interval_sensitivity
+                .unwrap_or(DEFAULT_CAPTURE_INTERVAL_SENSITIVITY),
+            frame_change_threshold: optional_config
+                .frame_change_threshold
+                .unwrap_or(DEFAULT_FRAME_CHANGE_THRESHOLD),
+            color_change_threshold: optional_config
+                .color_change_threshold
+                .unwrap_or(DEFAULT_COLOR_CHANGE_THRESHOLD),
+            max_submission_age: optional_config.max_submission_age,
             mode: optional_config.mode.unwrap_or(DEFAULT_COLOR_SELECTION_MODE),
             hue_shift: optional_config.hue_shift.unwrap_or(DEFAULT_HUE_SHIFT),
+            analogous_angle: optional_config
+                .analogous_angle
+                .unwrap_or(DEFAULT_ANALOGOUS_ANGLE),
+            split_complementary_angle: optional_config
+                .split_complementary_angle
+                .unwrap_or(DEFAULT_SPLIT_COMPLEMENTARY_ANGLE),
+            gradient: optional_config.gradient.unwrap_or_default(),
             color_format: optional_config.color_format.unwrap_or(DEFAULT_COLOR_FORMAT),
             brightness_factor: optional_config
                 .brightness_factor
                 .unwrap_or(DEFAULT_BRIGHTNESS_FACTOR),
+            color_grading: optional_config
+                .color_grading
+                .unwrap_or_else(default_color_grading),
+            smoothing: optional_config.smoothing,
         })
     }
 
@@ -131,10 +254,7 @@ impl DesktopDyeConfig {
     fn validate_optional_config(optional_config: &OptionalDesktopDyeConfig) -> Vec<String> {
         let mut errors = Vec::new();
 
-        if optional_config.ha_endpoint.is_none() {
-            errors.push("Missing Home Assistant endpoint in config file".to_string());
-        } else {
-            let ha_endpoint = optional_config.ha_endpoint.as_ref().unwrap();
+        if let Some(ha_endpoint) = &optional_config.ha_endpoint {
             if !ha_endpoint.starts_with("http://") && !ha_endpoint.starts_with("https://") {
                 errors.push(format!(
                     "Home Assistant endpoint must start with http:// or https://. Found \"{}\"",
@@ -155,6 +275,8 @@ impl DesktopDyeConfig {
                     ha_endpoint
                 ));
             }
+        } else {
+            errors.push("Missing Home Assistant endpoint in config file".to_string());
         }
 
         if optional_config.ha_token.is_none() {
@@ -176,25 +298,294 @@ impl DesktopDyeConfig {
             }
         }
 
-        if let Some(capture_interval) = optional_config.capture_interval {
-            if capture_interval <= 0.0 {
+        if let Some(algorithm) = &optional_config.algorithm {
+            errors.extend(algorithm.validate());
+        }
+
+        if let Some(kmeans) = &optional_config.kmeans {
+            errors.extend(kmeans.validate());
+        }
+
+        if let Some(hue_histogram) = &optional_config.hue_histogram {
+            errors.extend(hue_histogram.validate());
+        }
+
+        let intervals = [
+            ("Capture interval", optional_config.capture_interval),
+            ("Min capture interval", optional_config.min_capture_interval),
+            ("Max capture interval", optional_config.max_capture_interval),
+            ("Max submission age", optional_config.max_submission_age),
+        ];
+        for (name, interval) in intervals {
+            if let Some(interval) = interval {
+                errors.extend(Self::validate_interval(name, interval));
+            }
+        }
+
+        let min_capture_interval = optional_config
+            .min_capture_interval
+            .unwrap_or(DEFAULT_MIN_CAPTURE_INTERVAL);
+        let max_capture_interval = optional_config
+            .max_capture_interval
+            .unwrap_or(DEFAULT_MAX_CAPTURE_INTERVAL);
+        if min_capture_interval > max_capture_interval {
+            errors.push(format!(
+                "Min capture interval must not be greater than max capture interval. Found {} and {}",
+                min_capture_interval, max_capture_interval
+            ));
+        }
+
+        if let Some(capture_interval_sensitivity) = optional_config.capture_interval_sensitivity {
+            if !(capture_interval_sensitivity.is_finite() && capture_interval_sensitivity > 0.0) {
+                errors.push(format!(
+                    "Capture interval sensitivity must be a finite number greater than 0. Found {}",
+                    capture_interval_sensitivity
+                ));
+            }
+        }
+
+        if let Some(frame_change_threshold) = optional_config.frame_change_threshold {
+            if !(0.0..=1.0).contains(&frame_change_threshold) {
                 errors.push(format!(
-                    "Capture interval must be greater than 0. Found {}",
-                    capture_interval
+                    "Frame change threshold must be between 0 and 1. Found {}",
+                    frame_change_threshold
                 ));
-            } else if let Err(duration_err) =
-                std::time::Duration::try_from_secs_f64(capture_interval)
-            {
+            }
+        }
+
+        if let Some(color_change_threshold) = optional_config.color_change_threshold {
+            if !color_change_threshold.is_finite() || color_change_threshold < 0.0 {
                 errors.push(format!(
-                    "Capture interval invalid. Cannot time out for amount of seconds provided. Found {}. Error: {}",
-                    capture_interval,
-                    duration_err
+                    "Color change threshold must be 0 or greater. Found {}",
+                    color_change_threshold
                 ));
             }
         }
 
+        let harmony_angles = [
+            ("Analogous angle", optional_config.analogous_angle),
+            (
+                "Split complementary angle",
+                optional_config.split_complementary_angle,
+            ),
+        ];
+        for (name, angle) in harmony_angles {
+            if let Some(angle) = angle {
+                if !(angle > 0.0 && angle < 180.0) {
+                    errors.push(format!(
+                        "{} must be greater than 0 and less than 180. Found {}",
+                        name, angle
+                    ));
+                }
+            }
+        }
+
+        if let Some(gradient) = &optional_config.gradient {
+            errors.extend(gradient.validate());
+        }
+
+        if optional_config.source == Some(PixelSourceKind::Image) {
+            match &optional_config.image_path {
+                None => {
+                    errors.push("Missing image path in config file, which is required when the source is set to 'image'".to_string());
+                }
+                Some(image_path) if !image_path.exists() => {
+                    errors.push(format!(
+                        "Image path does not exist. Found \"{}\"",
+                        image_path.display()
+                    ));
+                }
+                Some(_) => {}
+            }
+        }
+
+        if let Some(screen_id) = &optional_config.screen_id {
+            errors.extend(screen_id.validate());
+        }
+
+        if let Some(screen_targets) = &optional_config.screen_targets {
+            for (screen_id, entity_id) in screen_targets {
+                if entity_id.is_empty() {
+                    errors.push(format!(
+                        "Home Assistant target entity ID for screen {} must not be empty",
+                        screen_id
+                    ));
+                }
+            }
+        }
+
+        if let Some(capture_region) = &optional_config.capture_region {
+            errors.extend(capture_region.validate("Capture region"));
+
+            let source = optional_config
+                .source
+                .as_ref()
+                .unwrap_or(&DEFAULT_PIXEL_SOURCE);
+            if source == &PixelSourceKind::Screen {
+                for (name, width, height) in Self::get_capture_sizes(optional_config) {
+                    if let Err(region_err) = capture_region.resolve(width, height) {
+                        errors.push(format!(
+                            "Capture region does not fit {}. {}",
+                            name, region_err
+                        ));
+                    }
+                }
+            }
+        }
+
+        if let Some(exclusion_regions) = &optional_config.exclusion_regions {
+            for (i, exclusion_region) in exclusion_regions.iter().enumerate() {
+                errors.extend(exclusion_region.validate(&format!("Exclusion region {}", i + 1)));
+            }
+        }
+
+        if let Some(edge_zones) = &optional_config.edge_zones {
+            errors.extend(edge_zones.validate());
+        }
+
+        if let Some(led_layout) = &optional_config.led_layout {
+            errors.extend(led_layout.validate());
+        }
+
+        if let Some(downsampling) = &optional_config.downsampling {
+            errors.extend(downsampling.validate());
+        }
+
+        if let Some(deduplication) = &optional_config.deduplication {
+            errors.extend(deduplication.validate());
+        }
+
+        if let Some(color_grading) = &optional_config.color_grading {
+            for (i, step) in color_grading.iter().enumerate() {
+                errors.extend(step.validate(&format!("Color grading step {}", i + 1)));
+            }
+        }
+
+        if let Some(smoothing) = &optional_config.smoothing {
+            errors.extend(smoothing.validate());
+        }
+
         errors
     }
+
+    /// Validates the given interval (in seconds) and returns a list of errors.
+    fn validate_interval(name: &str, interval: f64) -> Vec<String> {
+        if interval <= 0.0 {
+            return vec![format!(
+                "{} must be greater than 0. Found {}",
+                name, interval
+            )];
+        }
+
+        if let Err(duration_err) = std::time::Duration::try_from_secs_f64(interval) {
+            return vec![format!(
+                "{} invalid. Cannot time out for amount of seconds provided. Found {}. Error: {}",
+                name, interval, duration_err
+            )];
+        }
+
+        vec![]
+    }
+
+    /// Returns a name and the size of the frames that will be captured from the selected screens.
+    ///
+    /// When merging several screens, their frames are combined into one frame, so a single size
+    /// is returned. Returns an empty list if the screens cannot be listed or selected.
+    fn get_capture_sizes(optional_config: &OptionalDesktopDyeConfig) -> Vec<(String, u32, u32)> {
+        let screens = Screen::all()
+            .ok()
+            .and_then(|screens| {
+                ScreenSelection::select(optional_config.screen_id.as_ref(), &screens).ok()
+            })
+            .unwrap_or_default();
+
+        let multi_screen_mode = optional_config
+            .multi_screen_mode
+            .as_ref()
+            .unwrap_or(&DEFAULT_MULTI_SCREEN_MODE);
+        if screens.len() > 1 && multi_screen_mode == &MultiScreenMode::Merge {
+            let sizes = screens
+                .iter()
+                .map(Self::get_screen_capture_size)
+                .collect::<Vec<_>>();
+
+            return vec![(
+                "the combined screens".to_string(),
+                sizes.iter().map(|(width, _)| width).sum(),
+                sizes.iter().map(|(_, height)| *height).max().unwrap_or(0),
+            )];
+        }
+
+        screens
+            .iter()
+            .map(|screen| {
+                let (width, height) = Self::get_screen_capture_size(screen);
+                (format!("screen {}", screen.display_info.id), width, height)
+            })
+            .collect()
+    }
+
+    /// Returns the size in pixels of the frames captured from the given screen, taking the
+    /// screen's scale factor into account.
+    fn get_screen_capture_size(screen: &Screen) -> (u32, u32) {
+        let display_info = &screen.display_info;
+
+        (
+            (display_info.width as f32 * display_info.scale_factor) as u32,
+            (display_info.height as f32 * display_info.scale_factor) as u32,
+        )
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub enum PixelSourceKind {
+    #[serde(rename = "screen")]
+    Screen,
+    #[serde(rename = "image")]
+    Image,
+}
+
+impl Display for PixelSourceKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PixelSourceKind::Screen => write!(f, "Screen"),
+            PixelSourceKind::Image => write!(f, "Image"),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub enum MultiScreenMode {
+    #[serde(rename = "merge")]
+    Merge,
+    #[serde(rename = "separate")]
+    Separate,
+}
+
+impl Display for MultiScreenMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MultiScreenMode::Merge => write!(f, "Merge"),
+            MultiScreenMode::Separate => write!(f, "Separate"),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub enum CaptureIntervalMode {
+    #[serde(rename = "fixed")]
+    Fixed,
+    #[serde(rename = "adaptive")]
+    Adaptive,
+}
+
+impl Display for CaptureIntervalMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CaptureIntervalMode::Fixed => write!(f, "Fixed"),
+            CaptureIntervalMode::Adaptive => write!(f, "Adaptive"),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -205,6 +596,16 @@ pub enum ColorSelectionMode {
     Brightness,
     #[serde(rename = "hue_shift")]
     HueShift,
+    #[serde(rename = "complementary")]
+    Complementary,
+    #[serde(rename = "triadic")]
+    Triadic,
+    #[serde(rename = "analogous")]
+    Analogous,
+    #[serde(rename = "split_complementary")]
+    SplitComplementary,
+    #[serde(rename = "gradient")]
+    Gradient,
 }
 
 impl Display for ColorSelectionMode {
@@ -213,16 +614,84 @@ impl Display for ColorSelectionMode {
             ColorSelectionMode::Default => write!(f, "Default"),
             ColorSelectionMode::Brightness => write!(f, "Brightness"),
             ColorSelectionMode::HueShift => write!(f, "Hue Shift"),
+            ColorSelectionMode::Complementary => write!(f, "Complementary"),
+            ColorSelectionMode::Triadic => write!(f, "Triadic"),
+            ColorSelectionMode::Analogous => write!(f, "Analogous"),
+            ColorSelectionMode::SplitComplementary => write!(f, "Split Complementary"),
+            ColorSelectionMode::Gradient => write!(f, "Gradient"),
         }
     }
 }
 
+/// The algorithm used to calculate the dominant colors of a frame.
+///
+/// In the config file, this is the name of one of the built-in algorithms. Programs using the API
+/// can select a palette extractor they registered themselves (see `PaletteExtractorRegistry`)
+/// with `Registered`, which is looked up when colors are calculated.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(from = "String")]
 pub enum DominantColorAlgorithm {
-    #[serde(rename = "color_thief")]
     ColorThief,
-    #[serde(rename = "pigmnts")]
     Pigmnts,
+    KMeansLab,
+    Octree,
+    MedianCut,
+    HueHistogram,
+    Registered(String),
+}
+
+impl From<String> for DominantColorAlgorithm {
+    fn from(name: String) -> Self {
+        match name.as_str() {
+            "color_thief" => DominantColorAlgorithm::ColorThief,
+            "pigmnts" => DominantColorAlgorithm::Pigmnts,
+            "kmeans_lab" => DominantColorAlgorithm::KMeansLab,
+            "octree" => DominantColorAlgorithm::Octree,
+            "median_cut" => DominantColorAlgorithm::MedianCut,
+            "hue_histogram" => DominantColorAlgorithm::HueHistogram,
+            _ => DominantColorAlgorithm::Registered(name),
+        }
+    }
+}
+
+impl DominantColorAlgorithm {
+    /// The name of this algorithm, as used in the config file and to look up its palette
+    /// extractor.
+    pub fn name(&self) -> &str {
+        match self {
+            DominantColorAlgorithm::ColorThief => "color_thief",
+            DominantColorAlgorithm::Pigmnts => "pigmnts",
+            DominantColorAlgorithm::KMeansLab => "kmeans_lab",
+            DominantColorAlgorithm::Octree => "octree",
+            DominantColorAlgorithm::MedianCut => "median_cut",
+            DominantColorAlgorithm::HueHistogram => "hue_histogram",
+            DominantColorAlgorithm::Registered(name) => name,
+        }
+    }
+
+    /// Validates that this is one of the built-in algorithms and returns a list of errors.
+    pub fn validate(&self) -> Vec<String> {
+        // The names of the extractors don't depend on their settings.
+        let registry = PaletteExtractorRegistry::with_builtin_extractors(
+            &KMeansSettings::default(),
+            &HueHistogramSettings::default(),
+        );
+
+        match registry.get(self.name()) {
+            Some(_) => vec![],
+            None => vec![format!(
+                "Algorithm must be one of {}. Found \"{}\"",
+                registry.names().join(", "),
+                self
+            )],
+        }
+    }
+}
+
+impl Display for DominantColorAlgorithm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -0,0 +1,208 @@
+use anyhow::*;
+use serde::Deserialize;
+use std::fmt::{Display, Formatter};
+
+use crate::models::frame::Rect;
+
+/// A length along one axis of a frame.
+///
+/// In the config file, this is either an amount of pixels (`120`) or a percentage of the
+/// frame's size along that axis (`"25%"`).
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(try_from = "DimensionValue")]
+pub enum Dimension {
+    Pixels(u32),
+    Percentage(f64),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DimensionValue {
+    Pixels(u32),
+    Text(String),
+}
+
+impl TryFrom<DimensionValue> for Dimension {
+    type Error = String;
+
+    fn try_from(value: DimensionValue) -> std::result::Result<Self, Self::Error> {
+        match value {
+            DimensionValue::Pixels(pixels) => std::result::Result::Ok(Dimension::Pixels(pixels)),
+            DimensionValue::Text(text) => {
+                let text = text.trim();
+                if let Some(percentage) = text.strip_suffix('%') {
+                    percentage
+                        .trim()
+                        .parse::<f64>()
+                        .map(Dimension::Percentage)
+                        .map_err(|_| format!("Invalid percentage \"{}\"", text))
+                } else {
+                    text.strip_suffix("px")
+                        .unwrap_or(text)
+                        .trim()
+                        .parse::<u32>()
+                        .map(Dimension::Pixels)
+                        .map_err(|_| {
+                            format!(
+                                "Invalid dimension \"{}\", expected an amount of pixels (e.g. 120) or a percentage (e.g. \"25%\")",
+                                text
+                            )
+                        })
+                }
+            }
+        }
+    }
+}
+
+impl Dimension {
+    /// Converts this dimension to an amount of pixels, given the total size of the axis.
+    pub fn resolve(&self, total: u32) -> u32 {
+        match self {
+            Dimension::Pixels(pixels) => *pixels,
+            Dimension::Percentage(percentage) => {
+                (total as f64 * percentage / 100.0).round().max(0.0) as u32
+            }
+        }
+    }
+
+    /// Validates the parts of this dimension that do not depend on the frame size.
+    fn validate(&self, name: &str) -> Vec<String> {
+        match self {
+            Dimension::Percentage(percentage) if !(0.0..=100.0).contains(percentage) => {
+                vec![format!(
+                    "{} must be a percentage between 0% and 100%. Found {}",
+                    name, self
+                )]
+            }
+            _ => vec![],
+        }
+    }
+}
+
+impl Display for Dimension {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Dimension::Pixels(pixels) => write!(f, "{}px", pixels),
+            Dimension::Percentage(percentage) => write!(f, "{}%", percentage),
+        }
+    }
+}
+
+/// A rectangular area of a frame, with each side given as a [`Dimension`].
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct Region {
+    pub x: Dimension,
+    pub y: Dimension,
+    pub width: Dimension,
+    pub height: Dimension,
+}
+
+impl Region {
+    /// Converts this region to a rectangle in pixels for a frame of the given size.
+    ///
+    /// Returns an error if the region is empty or does not fit within the frame.
+    pub fn resolve(&self, frame_width: u32, frame_height: u32) -> Result<Rect> {
+        let rect = self.to_rect(frame_width, frame_height);
+
+        if rect.width == 0 || rect.height == 0 {
+            return Err(anyhow!(
+                "Region {} is empty for a frame of {}x{}",
+                self,
+                frame_width,
+                frame_height
+            ));
+        }
+
+        let fits = |start: u32, length: u32, total: u32| {
+            start
+                .checked_add(length)
+                .map(|end| end <= total)
+                .unwrap_or(false)
+        };
+        if !fits(rect.x, rect.width, frame_width) || !fits(rect.y, rect.height, frame_height) {
+            return Err(anyhow!(
+                "Region {} does not fit within a frame of {}x{}",
+                self,
+                frame_width,
+                frame_height
+            ));
+        }
+
+        Ok(rect)
+    }
+
+    /// Converts this region to a rectangle in pixels for a frame of the given size, and clamps it
+    /// to the bounds of the frame.
+    ///
+    /// Unlike [`Region::resolve`], this never fails: the part of the region that lies outside of
+    /// the frame is dropped, which can leave an empty rectangle.
+    pub fn resolve_clamped(&self, frame_width: u32, frame_height: u32) -> Rect {
+        self.to_rect(frame_width, frame_height)
+            .clamp(frame_width, frame_height)
+    }
+
+    fn to_rect(&self, frame_width: u32, frame_height: u32) -> Rect {
+        let (x, width) = resolve_span(&self.x, &self.width, frame_width);
+        let (y, height) = resolve_span(&self.y, &self.height, frame_height);
+
+        Rect::new(x, y, width, height)
+    }
+
+    /// Validates the parts of this region that do not depend on the frame size, and returns a
+    /// list of errors.
+    pub fn validate(&self, name: &str) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        errors.extend(self.x.validate(&format!("{} x", name)));
+        errors.extend(self.y.validate(&format!("{} y", name)));
+        errors.extend(self.width.validate(&format!("{} width", name)));
+        errors.extend(self.height.validate(&format!("{} height", name)));
+
+        for (offset, size, axis) in [
+            (&self.x, &self.width, "width"),
+            (&self.y, &self.height, "height"),
+        ] {
+            if let (Dimension::Percentage(offset), Dimension::Percentage(size)) = (offset, size) {
+                if offset + size > 100.0 {
+                    errors.push(format!(
+                        "{} exceeds the frame's {}. Found {}",
+                        name, axis, self
+                    ));
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+impl Display for Region {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "(x: {}, y: {}, width: {}, height: {})",
+            self.x, self.y, self.width, self.height
+        )
+    }
+}
+
+/// Converts an offset and size along an axis of the given total size to a start and length in
+/// pixels.
+///
+/// Rounding a percentage offset and size on their own can make the span end a pixel past the
+/// frame (e.g. the right half of a frame of odd width), so a span given in percentages ends at
+/// its rounded end edge instead, and a percentage size never reaches past the frame.
+fn resolve_span(offset: &Dimension, size: &Dimension, total: u32) -> (u32, u32) {
+    let start = offset.resolve(total);
+
+    match (offset, size) {
+        (Dimension::Percentage(offset), Dimension::Percentage(size)) => {
+            let end = Dimension::Percentage(offset + size).resolve(total);
+            (start, end.saturating_sub(start))
+        }
+        (_, Dimension::Percentage(_)) => {
+            (start, size.resolve(total).min(total.saturating_sub(start)))
+        }
+        _ => (start, size.resolve(total)),
+    }
+}
@@ -0,0 +1,100 @@
+use anyhow::*;
+use screenshots::Screen;
+use serde::Deserialize;
+use std::fmt::{Display, Formatter};
+
+/// The screens to capture colors from.
+///
+/// In the config file, this is either a single screen ID (`1`), a list of screen IDs (`[1, 2]`)
+/// or `all`.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(try_from = "ScreenSelectionValue")]
+pub enum ScreenSelection {
+    Ids(Vec<u32>),
+    All,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ScreenSelectionValue {
+    Id(u32),
+    Ids(Vec<u32>),
+    Keyword(String),
+}
+
+impl TryFrom<ScreenSelectionValue> for ScreenSelection {
+    type Error = String;
+
+    fn try_from(value: ScreenSelectionValue) -> std::result::Result<Self, Self::Error> {
+        match value {
+            ScreenSelectionValue::Id(id) => std::result::Result::Ok(ScreenSelection::Ids(vec![id])),
+            ScreenSelectionValue::Ids(ids) => std::result::Result::Ok(ScreenSelection::Ids(ids)),
+            ScreenSelectionValue::Keyword(keyword) if keyword == "all" => {
+                std::result::Result::Ok(ScreenSelection::All)
+            }
+            ScreenSelectionValue::Keyword(keyword) => Err(format!(
+                "Invalid screen ID \"{}\", expected a screen ID, a list of screen IDs or \"all\"",
+                keyword
+            )),
+        }
+    }
+}
+
+impl ScreenSelection {
+    /// Selects the screens matching this selection from the given screens, ordered by their
+    /// position from left to right.
+    ///
+    /// If no selection is given, the primary screen is selected. Returns an error if any of the
+    /// selected screens cannot be found.
+    pub fn select(selection: Option<&ScreenSelection>, screens: &[Screen]) -> Result<Vec<Screen>> {
+        let mut selected_screens = match selection {
+            None => screens
+                .iter()
+                .find(|screen| screen.display_info.is_primary)
+                .map(|screen| vec![*screen])
+                .ok_or_else(|| anyhow!("Failed to find primary screen")),
+            Some(ScreenSelection::All) => Ok(screens.to_vec()),
+            Some(ScreenSelection::Ids(ids)) => ids
+                .iter()
+                .map(|id| {
+                    screens
+                        .iter()
+                        .find(|screen| screen.display_info.id == *id)
+                        .copied()
+                        .ok_or_else(|| anyhow!("Failed to find screen with id {}", id))
+                })
+                .collect::<Result<Vec<_>>>(),
+        }?;
+
+        selected_screens.sort_by_key(|screen| (screen.display_info.x, screen.display_info.y));
+        selected_screens.dedup_by_key(|screen| screen.display_info.id);
+
+        Ok(selected_screens)
+    }
+
+    /// Validates this selection and returns a list of errors.
+    pub fn validate(&self) -> Vec<String> {
+        match self {
+            ScreenSelection::Ids(ids) if ids.is_empty() => {
+                vec!["Screen ID list must contain at least one screen ID".to_string()]
+            }
+            _ => vec![],
+        }
+    }
+}
+
+impl Display for ScreenSelection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScreenSelection::Ids(ids) => write!(
+                f,
+                "{}",
+                ids.iter()
+                    .map(|id| id.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            ScreenSelection::All => write!(f, "all"),
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use serde::Deserialize;
+use std::fmt::{Display, Formatter};
+
+pub const DEFAULT_SMOOTHING_METHOD: SmoothingMethod = SmoothingMethod::Ema;
+pub const DEFAULT_SMOOTHING_TIME_CONSTANT: f64 = 1.0;
+pub const MAX_SMOOTHING_SEND_RATE: f64 = 20.0;
+
+/// Gradually moves the submitted colors towards newly captured colors, instead of jumping to
+/// them at once.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct Smoothing {
+    #[serde(default = "default_method")]
+    pub method: SmoothingMethod,
+    /// The amount of seconds it takes the colors to move most of the way (about 60%) towards
+    /// newly captured colors.
+    #[serde(default = "default_time_constant")]
+    pub time_constant: f64,
+    /// The amount of times per second to submit intermediate colors between captures, or
+    /// `None` to only submit colors after every capture.
+    pub send_rate: Option<f64>,
+}
+
+fn default_method() -> SmoothingMethod {
+    DEFAULT_SMOOTHING_METHOD
+}
+
+fn default_time_constant() -> f64 {
+    DEFAULT_SMOOTHING_TIME_CONSTANT
+}
+
+impl Default for Smoothing {
+    fn default() -> Self {
+        Self {
+            method: DEFAULT_SMOOTHING_METHOD,
+            time_constant: DEFAULT_SMOOTHING_TIME_CONSTANT,
+            send_rate: None,
+        }
+    }
+}
+
+impl Smoothing {
+    /// Validates this smoothing and returns a list of errors.
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        if !self.time_constant.is_finite() || self.time_constant <= 0.0 {
+            errors.push(format!(
+                "Smoothing time constant must be greater than 0. Found {}",
+                self.time_constant
+            ));
+        }
+
+        if let Some(send_rate) = self.send_rate {
+            if !(send_rate > 0.0 && send_rate <= MAX_SMOOTHING_SEND_RATE) {
+                errors.push(format!(
+                    "Smoothing send rate must be greater than 0 and at most {}. Found {}",
+                    MAX_SMOOTHING_SEND_RATE, send_rate
+                ));
+            }
+        }
+
+        errors
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub enum SmoothingMethod {
+    /// Exponential moving average: moves a fixed share of the remaining distance per second,
+    /// slowing down as the colors approach their targets.
+    #[serde(rename = "ema")]
+    Ema,
+    /// Critically damped spring: starts slowly, speeds up and settles on the targets without
+    /// overshooting them.
+    #[serde(rename = "spring")]
+    Spring,
+}
+
+impl Display for SmoothingMethod {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SmoothingMethod::Ema => write!(f, "Exponential moving average"),
+            SmoothingMethod::Spring => write!(f, "Spring"),
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use crate::config::DesktopDyeConfig;
+
+/// The factor by which the interval grows per capture while the screen is more static than
+/// before, so the interval doesn't jump to the maximum after a single quiet moment.
+const INTERVAL_GROWTH_FACTOR: f64 = 1.5;
+
+/// Adapts the time between captures to the activity on the screen.
+///
+/// The more two consecutive captures differ, the closer the interval gets to the minimum
+/// interval. Shorter intervals are used right away, while longer intervals are grown towards
+/// gradually.
+#[derive(Debug, Clone)]
+pub struct AdaptiveInterval {
+    min_interval: f64,
+    max_interval: f64,
+    sensitivity: f64,
+    interval: f64,
+}
+
+impl AdaptiveInterval {
+    /// Creates an adaptive interval starting at `initial_interval` (in seconds), limited to the
+    /// given minimum and maximum.
+    pub fn new(
+        initial_interval: f64,
+        min_interval: f64,
+        max_interval: f64,
+        sensitivity: f64,
+    ) -> Self {
+        Self {
+            min_interval,
+            max_interval,
+            sensitivity,
+            interval: initial_interval.clamp(min_interval, max_interval),
+        }
+    }
+
+    /// Creates an adaptive interval from the capture interval settings of the given config.
+    pub fn from_config(config: &DesktopDyeConfig) -> Self {
+        Self::new(
+            config.capture_interval,
+            config.min_capture_interval,
+            config.max_capture_interval,
+            config.capture_interval_sensitivity,
+        )
+    }
+
+    /// The current interval, in seconds.
+    pub fn interval(&self) -> f64 {
+        self.interval
+    }
+
+    /// Updates the interval with the difference between the last two captures (from 0 to 1,
+    /// see [`crate::functions::FrameFingerprint::difference`]) and returns the new interval.
+    pub fn update(&mut self, difference: f64) -> f64 {
+        let activity = (difference * self.sensitivity).clamp(0.0, 1.0);
+        let target_interval =
+            self.max_interval - (self.max_interval - self.min_interval) * activity;
+
+        self.interval = if target_interval < self.interval {
+            target_interval
+        } else {
+            (self.interval * INTERVAL_GROWTH_FACTOR).min(target_interval)
+        };
+
+        self.interval
+    }
+}
@@ -0,0 +1,56 @@
+use anyhow::*;
+use screenshots::Screen;
+
+use crate::config::{DesktopDyeConfig, MultiScreenMode};
+
+/// A group of screens whose colors are calculated together, and the entity they are submitted
+/// to.
+#[derive(Debug, Clone)]
+pub struct ScreenTarget {
+    /// The screens of this target, which are combined into a single frame if there are several.
+    pub screens: Vec<Screen>,
+    pub entity_id: String,
+}
+
+/// Assigns the given screens to the entities their colors are submitted to.
+///
+/// When multiple screens are merged, they share a single target, which submits to the configured
+/// target entity. Otherwise, every screen gets its own target, which submits to the entity set
+/// for it in `screen_targets` (or the configured target entity if none is set). Returns an error
+/// if several separate screens would submit to the same entity.
+pub fn assign_screen_targets(
+    config: &DesktopDyeConfig,
+    screens: Vec<Screen>,
+) -> Result<Vec<ScreenTarget>> {
+    if config.multi_screen_mode == MultiScreenMode::Merge {
+        return Ok(vec![ScreenTarget {
+            screens,
+            entity_id: config.ha_target_entity_id.clone(),
+        }]);
+    }
+
+    let mut targets: Vec<ScreenTarget> = vec![];
+    for screen in screens {
+        let screen_id = screen.display_info.id;
+        let entity_id = config
+            .screen_targets
+            .get(&screen_id)
+            .cloned()
+            .unwrap_or_else(|| config.ha_target_entity_id.clone());
+
+        if targets.iter().any(|target| target.entity_id == entity_id) {
+            return Err(anyhow!(
+                "Multiple screens would submit their colors to {}. Please set a different target entity ID for screen {} in 'screen_targets'",
+                entity_id,
+                screen_id
+            ));
+        }
+
+        targets.push(ScreenTarget {
+            screens: vec![screen],
+            entity_id,
+        });
+    }
+
+    Ok(targets)
+}
@@ -1,22 +1,77 @@
-use lab::Lab;
+use std::collections::HashMap;
+
 use prisma::Rgb;
 
-use crate::{config::DominantColorAlgorithm, models::colors::RgbColor};
+use anyhow::*;
+
+use crate::{
+    config::DominantColorAlgorithm,
+    functions::PaletteExtractorRegistry,
+    models::colors::{DominantColor, RgbColor},
+};
 
+/// Calculates the dominant colors of the given pixels using the extractor registered for the
+/// given algorithm.
+///
+/// Returns at most `sample_size` colors, ordered from most to least dominant, or an error if no
+/// extractor is registered for the algorithm.
 pub fn calculate_dominant_colors(
-    pixels: &Vec<RgbColor>,
+    pixels: &[RgbColor],
+    registry: &PaletteExtractorRegistry,
     alg: &DominantColorAlgorithm,
     sample_size: &u8,
-) -> Vec<RgbColor> {
-    match alg {
-        DominantColorAlgorithm::Pigmnts => pigmnts_alg(pixels, *sample_size),
-        DominantColorAlgorithm::ColorThief => color_thief_alg(pixels, *sample_size),
-    }
+) -> Result<Vec<DominantColor>> {
+    registry.extract(alg.name(), pixels, *sample_size)
 }
 
-fn pigmnts_alg(pixels: &Vec<RgbColor>, sample_size: u8) -> Vec<RgbColor> {
+/// Converts colors with their pixel populations into dominant colors, weighted by the share of
+/// all pixels they represent.
+pub(crate) fn with_weights(
+    colors: Vec<(RgbColor, usize)>,
+    pixel_count: usize,
+) -> Vec<DominantColor> {
+    colors
+        .into_iter()
+        .map(|(color, population)| {
+            DominantColor::new(color, population as f64 / pixel_count.max(1) as f64)
+        })
+        .collect()
+}
+
+/// Orders the given colors from most to least dominant, dropping colors that don't represent
+/// any pixels.
+fn sorted_by_weight(mut colors: Vec<DominantColor>) -> Vec<DominantColor> {
+    colors.retain(|dominant_color| dominant_color.weight > 0.0);
+    // The sort is stable, so colors of equal weight keep the order of the algorithm.
+    colors.sort_by(|a, b| {
+        b.weight
+            .partial_cmp(&a.weight)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    colors
+}
+
+/// pigmnts splits the pixels into this many equally sized chunks to assign them to clusters in
+/// parallel, ignoring the pixels that don't fit in a chunk.
+const PIGMNTS_THREAD_COUNT: usize = 5;
+
+pub(crate) fn pigmnts_alg(pixels: &[RgbColor], sample_size: u8) -> Vec<DominantColor> {
+    if pixels.is_empty() || sample_size == 0 {
+        return vec![];
+    }
+
+    // With fewer pixels than chunks, pigmnts wouldn't assign any pixel to a cluster. Repeating
+    // the pixels doesn't change their proportions.
+    let repetitions = if pixels.len() < PIGMNTS_THREAD_COUNT {
+        PIGMNTS_THREAD_COUNT
+    } else {
+        1
+    };
     let lab_values = pixels
         .iter()
+        .cycle()
+        .take(pixels.len() * repetitions)
         .map(|rgb| pigmnts::color::LAB::from_rgb(rgb.red(), rgb.green(), rgb.blue()))
         .collect::<Vec<_>>();
 
@@ -27,29 +82,26 @@ fn pigmnts_alg(pixels: &Vec<RgbColor>, sample_size: u8) -> Vec<RgbColor> {
         None,
     );
 
-    let mut colors = colors_res
+    let colors = colors_res
         .into_iter()
         .map(|(color, dominance)| {
-            let [r, g, b] = Lab::to_rgb(&Lab {
-                l: color.l,
-                a: color.a,
-                b: color.b,
-            });
+            // pigmnts uses its own RGB color space, so its colors have to be converted back
+            // with its own conversion.
+            let rgb = pigmnts::color::RGB::from(&color);
 
-            (Rgb::new(r, g, b), dominance)
+            DominantColor::new(Rgb::new(rgb.r, rgb.g, rgb.b), dominance as f64)
         })
-        .collect::<Vec<_>>();
+        .collect();
 
-    colors.sort_by(|(_, dominance_a), (_, dominance_b)| {
-        dominance_a
-            .partial_cmp(dominance_b)
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
-
-    colors.into_iter().map(|(rgb, _)| rgb).collect()
+    sorted_by_weight(colors)
 }
 
-fn color_thief_alg(pixels: &Vec<RgbColor>, sample_size: u8) -> Vec<RgbColor> {
+pub(crate) fn color_thief_alg(pixels: &[RgbColor], sample_size: u8) -> Vec<DominantColor> {
+    if pixels.is_empty() || sample_size == 0 {
+        return vec![];
+    }
+
+    // color_thief requires at least two colors to be requested.
     let colors_res = color_thief::get_palette(
         &pixels
             .iter()
@@ -57,12 +109,57 @@ fn color_thief_alg(pixels: &Vec<RgbColor>, sample_size: u8) -> Vec<RgbColor> {
             .collect::<Vec<_>>(),
         color_thief::ColorFormat::Rgb,
         1,
-        sample_size,
+        sample_size.max(2),
     );
 
-    colors_res
+    let palette = colors_res
         .unwrap()
         .into_iter()
+        .take(sample_size as usize)
         .map(|color| Rgb::new(color.r, color.g, color.b))
-        .collect()
+        .collect::<Vec<RgbColor>>();
+
+    // color_thief doesn't report how many pixels each color represents, so every pixel is
+    // counted towards the nearest color of the palette.
+    let mut populations = vec![0; palette.len()];
+    for (rgb, count) in count_unique_colors(pixels) {
+        let distance = |color: &RgbColor| {
+            (0..3)
+                .map(|channel| {
+                    let value = [color.red(), color.green(), color.blue()][channel];
+                    (value as i32 - rgb[channel] as i32).pow(2)
+                })
+                .sum::<i32>()
+        };
+
+        if let Some((nearest, _)) = palette
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, color)| distance(color))
+        {
+            populations[nearest] += count;
+        }
+    }
+
+    sorted_by_weight(with_weights(
+        palette.into_iter().zip(populations).collect(),
+        pixels.len(),
+    ))
+}
+
+/// Returns every unique color of the given pixels, along with how often it occurs.
+///
+/// The colors are sorted, so the result doesn't depend on the order of the pixels.
+pub(crate) fn count_unique_colors(pixels: &[RgbColor]) -> Vec<([u8; 3], usize)> {
+    let mut counts = HashMap::new();
+    for pixel in pixels {
+        *counts
+            .entry([pixel.red(), pixel.green(), pixel.blue()])
+            .or_insert(0) += 1;
+    }
+
+    let mut counts = counts.into_iter().collect::<Vec<_>>();
+    counts.sort_unstable();
+
+    counts
 }
@@ -0,0 +1,43 @@
+use anyhow::*;
+
+use crate::{
+    config::{DesktopDyeConfig, EdgeZones},
+    functions::{calculate_dominant_colors, downsample_frame, PaletteExtractorRegistry},
+    models::{colors::RgbColor, frame::Frame},
+};
+
+/// Calculates the most dominant color of every edge zone of the given frame.
+///
+/// The colors are returned in the same order as the zones (see [`EdgeZones::resolve`]).
+/// Zones in which every pixel is excluded are returned as black. If downsampling is configured,
+/// every zone is downsampled on its own before its color is calculated with the configured
+/// algorithm.
+pub fn calculate_edge_zone_colors(
+    frame: &Frame,
+    edge_zones: &EdgeZones,
+    config: &DesktopDyeConfig,
+    registry: &PaletteExtractorRegistry,
+) -> Result<Vec<RgbColor>> {
+    edge_zones
+        .resolve(frame.width, frame.height)
+        .iter()
+        .map(|rect| {
+            let mut zone = frame.crop(rect);
+            if let Some(downsampling) = &config.downsampling {
+                zone = downsample_frame(&zone, downsampling);
+            }
+
+            let pixels = zone.included_pixels();
+            if pixels.is_empty() {
+                return Ok(RgbColor::new(0, 0, 0));
+            }
+
+            Ok(
+                calculate_dominant_colors(&pixels, registry, &config.algorithm, &1)?
+                    .first()
+                    .map(|dominant_color| dominant_color.color)
+                    .unwrap_or_else(|| RgbColor::new(0, 0, 0)),
+            )
+        })
+        .collect()
+}
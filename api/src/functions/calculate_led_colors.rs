@@ -0,0 +1,38 @@
+use crate::{
+    config::LedLayout,
+    models::{colors::RgbColor, frame::Frame},
+};
+
+/// Calculates the color of every LED in the given layout, by averaging the pixels of the frame
+/// within each LED's sampling box.
+///
+/// The colors are returned in the order of the LEDs on the strip (see [`LedLayout::resolve`]).
+/// LEDs whose sampling box only contains excluded pixels are returned as black.
+pub fn calculate_led_colors(frame: &Frame, led_layout: &LedLayout) -> Vec<RgbColor> {
+    led_layout
+        .resolve(frame.width, frame.height)
+        .iter()
+        .map(|rect| average_color(&frame.crop(rect).included_pixels()))
+        .collect()
+}
+
+fn average_color(pixels: &[RgbColor]) -> RgbColor {
+    if pixels.is_empty() {
+        return RgbColor::new(0, 0, 0);
+    }
+
+    let (red, green, blue) = pixels.iter().fold((0_u64, 0_u64, 0_u64), |sum, pixel| {
+        (
+            sum.0 + pixel.red() as u64,
+            sum.1 + pixel.green() as u64,
+            sum.2 + pixel.blue() as u64,
+        )
+    });
+    let count = pixels.len() as u64;
+
+    RgbColor::new(
+        (red / count) as u8,
+        (green / count) as u8,
+        (blue / count) as u8,
+    )
+}
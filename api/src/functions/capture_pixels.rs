@@ -2,13 +2,283 @@ use anyhow::*;
 use prisma::Rgb;
 use screenshots::Screen;
 
-use crate::models::colors::RgbColor;
+use crate::{
+    config::{DesktopDyeConfig, Downsampling, DownsamplingMethod, Region},
+    functions::{downsample_frame::BlockAverager, PixelSource},
+    models::{
+        colors::RgbColor,
+        frame::{Frame, Rect},
+    },
+};
 
-pub fn capture_pixels(screen: &Screen) -> Result<Vec<RgbColor>> {
+#[cfg(target_os = "linux")]
+mod x11;
+
+/// Captures a frame from the given source and prepares it for color calculation.
+///
+/// The configured exclusion regions and exclusion mask (if any) are applied first, after which
+/// the frame is cropped to the configured capture region (if any).
+///
+/// With box downsampling, and as long as no pixels have to be excluded and no edge zones or LED
+/// layout need the full frame, the source is asked to downsample the frame while capturing it.
+pub fn capture_frame(config: &DesktopDyeConfig, source: &mut dyn PixelSource) -> Result<Frame> {
+    let mut frame = match capture_downsampling(config) {
+        Some(downsampling) => source.capture_downsampled(&|width, height| {
+            let (width, height) = config
+                .capture_region
+                .as_ref()
+                .and_then(|region| region.resolve(width, height).ok())
+                .map(|rect| (rect.width, rect.height))
+                .unwrap_or((width, height));
+            downsampling.factor(width, height)
+        }),
+        None => source.capture(),
+    }
+    .context(format!("Failed to capture {}", source.description()))?;
+
+    // Exclusion regions are not checked against the size of every screen, so the part of a
+    // region that does not fit the frame is ignored instead of failing the capture.
+    let (source_width, source_height) = frame.source_size();
+    for exclusion_region in &config.exclusion_regions {
+        let rect = exclusion_region
+            .resolve_clamped(source_width, source_height)
+            .downscale(frame.scale);
+        frame.exclude_rect(&rect);
+    }
+
+    if let Some(exclusion_mask) = &config.exclusion_mask {
+        exclusion_mask.apply(&mut frame);
+    }
+
+    if let Some(capture_region) = &config.capture_region {
+        let rect =
+            resolve_region(capture_region, &frame).context("Failed to apply capture region")?;
+        frame = frame.crop(&rect);
+    }
+
+    Ok(frame)
+}
+
+/// Returns the downsampling to apply while capturing, if the frame can be downsampled before
+/// exclusions are applied and colors are calculated.
+fn capture_downsampling(config: &DesktopDyeConfig) -> Option<&Downsampling> {
+    let can_downsample = config.exclusion_regions.is_empty()
+        && config.exclusion_mask.is_none()
+        && config.edge_zones.is_none()
+        && config.led_layout.is_none();
+
+    config
+        .downsampling
+        .as_ref()
+        .filter(|downsampling| can_downsample && downsampling.method == DownsamplingMethod::Box)
+}
+
+/// Resolves the given region against the size of the source the frame was captured from, and
+/// maps it onto the (possibly downsampled) frame.
+fn resolve_region(region: &Region, frame: &Frame) -> Result<Rect> {
+    let (width, height) = frame.source_size();
+
+    Ok(region.resolve(width, height)?.downscale(frame.scale))
+}
+
+/// The order of the channels of each pixel in a raw frame buffer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RawPixelFormat {
+    Bgra,
+    Rgba,
+}
+
+/// A raw, uncompressed frame buffer with 4 bytes per pixel, as returned by most screen capture
+/// APIs.
+#[derive(Debug, Clone, Copy)]
+pub struct RawPixels<'a> {
+    pub bytes: &'a [u8],
+    pub width: u32,
+    pub height: u32,
+    /// The amount of bytes per row, which can be larger than `width * 4` when rows are padded.
+    pub stride: usize,
+    pub format: RawPixelFormat,
+}
+
+/// Captures screens and returns their contents as frames.
+///
+/// Only on Linux under X11, the screen's raw frame buffer is converted (and downsampled) to a
+/// frame directly, through a connection that is kept open between captures. On every other
+/// platform and under Wayland, this falls back to decoding the PNG image captured by
+/// `screenshots`, which is never downsampled. If capturing through X11 fails, the failure is
+/// logged once and every later capture uses the fallback.
+pub struct ScreenCapturer {
+    #[cfg(target_os = "linux")]
+    x11: X11State,
+}
+
+#[cfg(target_os = "linux")]
+enum X11State {
+    NotConnected,
+    Connected(x11::X11Connection),
+    /// Not available in a Wayland session.
+    Unavailable,
+    Failed,
+}
+
+impl Default for ScreenCapturer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScreenCapturer {
+    pub fn new() -> Self {
+        Self {
+            #[cfg(target_os = "linux")]
+            x11: X11State::NotConnected,
+        }
+    }
+
+    /// Captures the given screen, downsampled by the given factor where the screen's raw frame
+    /// buffer is available.
+    ///
+    /// The `scale` of the returned frame is the factor it was actually downsampled by, which is
+    /// 1 for the fallback.
+    pub fn capture(&mut self, screen: &Screen, downsample_factor: u32) -> Result<Frame> {
+        #[cfg(target_os = "linux")]
+        if let Some(frame) = self.capture_x11(screen, downsample_factor) {
+            return Ok(frame);
+        }
+
+        capture_png_pixels(screen)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn capture_x11(&mut self, screen: &Screen, downsample_factor: u32) -> Option<Frame> {
+        if let X11State::NotConnected = self.x11 {
+            if !x11::is_available() {
+                self.x11 = X11State::Unavailable;
+                return None;
+            }
+
+            self.x11 = match x11::X11Connection::connect() {
+                Result::Ok(connection) => X11State::Connected(connection),
+                Err(e) => {
+                    println!(
+                        "Failed to connect to X11, capturing screenshots instead (which is slower): {}",
+                        e
+                    );
+                    X11State::Failed
+                }
+            };
+        }
+
+        let result = match &self.x11 {
+            X11State::Connected(connection) => {
+                connection.capture_screen(&screen.display_info, downsample_factor)
+            }
+            _ => return None,
+        };
+        match result {
+            Result::Ok(frame) => Some(frame),
+            Err(e) => {
+                println!(
+                    "Failed to capture the screen through X11, capturing screenshots from now on (which is slower): {}",
+                    e
+                );
+                self.x11 = X11State::Failed;
+                None
+            }
+        }
+    }
+}
+
+/// Captures the given screen as an encoded PNG image and decodes it into a frame.
+///
+/// This is slower than converting the raw frame buffer, but works on every platform supported by
+/// `screenshots`.
+pub fn capture_png_pixels(screen: &Screen) -> Result<Frame> {
     let image = screen.capture()?;
-    let raw_png_buffer = image.buffer();
 
-    let decoder = png::Decoder::new(&raw_png_buffer[..]);
+    decode_png_pixels(image.buffer())
+}
+
+/// Converts a raw frame buffer into a frame, downsampling it by the given factor in the same pass.
+///
+/// Every block of `downsample_factor` by `downsample_factor` pixels is averaged into a single
+/// pixel (blocks at the right and bottom edges may be smaller). A factor of 1 keeps every pixel.
+/// The `scale` of the returned frame is set to the factor.
+pub fn convert_raw_pixels(raw: &RawPixels, downsample_factor: u32) -> Result<Frame> {
+    let (width, height) = (raw.width as usize, raw.height as usize);
+    if raw.stride < width * 4 {
+        return Err(anyhow!(
+            "Raw frame stride of {} bytes is too small for a width of {} pixels",
+            raw.stride,
+            width
+        ));
+    }
+    if height > 0 && raw.bytes.len() < raw.stride * (height - 1) + width * 4 {
+        return Err(anyhow!(
+            "Raw frame buffer of {} bytes is too small for {}x{} pixels",
+            raw.bytes.len(),
+            width,
+            height
+        ));
+    }
+
+    let (red, green, blue) = match raw.format {
+        RawPixelFormat::Bgra => (2, 1, 0),
+        RawPixelFormat::Rgba => (0, 1, 2),
+    };
+    let row = |y: usize| &raw.bytes[y * raw.stride..y * raw.stride + width * 4];
+
+    let factor = downsample_factor.max(1) as usize;
+    if factor == 1 {
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            pixels.extend(
+                row(y)
+                    .chunks_exact(4)
+                    .map(|pixel| Rgb::new(pixel[red], pixel[green], pixel[blue])),
+            );
+        }
+
+        return Ok(Frame::new(raw.width, raw.height, pixels));
+    }
+
+    let (output_width, output_height) = (width.div_ceil(factor), height.div_ceil(factor));
+    let mut averager = BlockAverager::new(output_width);
+    let mut averages = Vec::with_capacity(output_width * output_height);
+
+    for output_y in 0..output_height {
+        for y in output_y * factor..((output_y + 1) * factor).min(height) {
+            for (block, pixels) in row(y).chunks(factor * 4).enumerate() {
+                // The channels are summed in the order of the buffer, and only put in RGB order
+                // once the block is averaged.
+                for pixel in pixels.chunks_exact(4) {
+                    averager.add(block, [pixel[0], pixel[1], pixel[2]]);
+                }
+            }
+        }
+
+        averager.finish_row_ordered(&mut averages, [red, green, blue]);
+    }
+
+    // Every pixel is included, so every block has an average.
+    let pixels = averages.into_iter().flatten().collect();
+
+    let mut frame = Frame::new(output_width as u32, output_height as u32, pixels);
+    frame.scale = factor as u32;
+
+    Ok(frame)
+}
+
+/// Decodes an encoded PNG image into a frame.
+///
+/// Every color type (grayscale, RGB, indexed, with or without alpha) and bit depth is supported.
+/// Images with more than 8 bits per channel are reduced to 8 bits. Translucent pixels are
+/// composited on black, and fully transparent pixels are excluded from the frame.
+pub fn decode_png_pixels(raw_png_buffer: &[u8]) -> Result<Frame> {
+    let mut decoder = png::Decoder::new(raw_png_buffer);
+    // Expand indexed, low bit depth and transparency chunk (tRNS) images to 8-bit grayscale or
+    // RGB (with alpha), and strip 16-bit images down to 8 bits.
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
     let mut reader = decoder.read_info()?;
     let mut pixel_buffer = vec![0; reader.output_buffer_size()];
     // Read the next frame. An APNG might contain multiple frames.
@@ -16,31 +286,56 @@ pub fn capture_pixels(screen: &Screen) -> Result<Vec<RgbColor>> {
 
     if info.bit_depth != png::BitDepth::Eight {
         return Err(anyhow!(
-            "Only 8-bit PNGs are supported, but the bit depth of the screen capture is {:?}",
+            "Failed to convert the PNG image to 8 bits per channel, got a bit depth of {:?}",
             info.bit_depth
         ));
     }
 
-    let mut rgb_bytes = pixel_buffer[..info.buffer_size()].to_vec();
-    match info.color_type {
-        png::ColorType::Rgb => {}
-        png::ColorType::Rgba => {
-            rgb_bytes = rgb_bytes
-                .chunks_exact(4)
-                .flat_map(|chunk| &chunk[..3])
-                .copied()
-                .collect::<Vec<_>>();
-        }
-        _ => {
+    let bytes = &pixel_buffer[..info.buffer_size()];
+    let (pixels, alphas): (Vec<_>, Vec<_>) = match info.color_type {
+        png::ColorType::Grayscale => bytes
+            .iter()
+            .map(|value| (Rgb::new(*value, *value, *value), u8::MAX))
+            .unzip(),
+        png::ColorType::GrayscaleAlpha => bytes
+            .chunks_exact(2)
+            .map(|chunk| (Rgb::new(chunk[0], chunk[0], chunk[0]), chunk[1]))
+            .unzip(),
+        png::ColorType::Rgb => bytes
+            .chunks_exact(3)
+            .map(|chunk| (Rgb::new(chunk[0], chunk[1], chunk[2]), u8::MAX))
+            .unzip(),
+        png::ColorType::Rgba => bytes
+            .chunks_exact(4)
+            .map(|chunk| (Rgb::new(chunk[0], chunk[1], chunk[2]), chunk[3]))
+            .unzip(),
+        png::ColorType::Indexed => {
             return Err(anyhow!(
-                "Only RGB and RGBA PNGs screen captures are supported, but the color type is {:?}",
-                info.color_type
+                "Failed to expand the palette of the indexed PNG image"
             ))
         }
+    };
+
+    let mut frame = Frame::new(info.width, info.height, pixels);
+    if alphas.iter().any(|alpha| *alpha < u8::MAX) {
+        for (pixel, alpha) in frame.pixels.iter_mut().zip(&alphas) {
+            *pixel = composite_on_black(*pixel, *alpha);
+        }
     }
+    if alphas.contains(&0) {
+        let width = frame.width;
+        frame.exclude_where(|x, y| alphas[(y * width + x) as usize] == 0);
+    }
+
+    Ok(frame)
+}
+
+fn composite_on_black(pixel: RgbColor, alpha: u8) -> RgbColor {
+    let composite = |value: u8| ((value as u32 * alpha as u32 + 127) / u8::MAX as u32) as u8;
 
-    Ok(rgb_bytes
-        .chunks_exact(4)
-        .map(|chunk| Rgb::new(chunk[0], chunk[1], chunk[2]))
-        .collect::<Vec<_>>())
+    Rgb::new(
+        composite(pixel.red()),
+        composite(pixel.green()),
+        composite(pixel.blue()),
+    )
 }
@@ -0,0 +1,103 @@
+use anyhow::*;
+use screenshots::DisplayInfo;
+use xcb::x::{Drawable, GetImage, ImageFormat, ImageOrder, Window};
+
+use super::{convert_raw_pixels, RawPixelFormat, RawPixels};
+use crate::models::frame::Frame;
+
+/// Indicates whether screens can be captured through X11, which is not the case in a Wayland
+/// session (even though an X11 server may be running for XWayland).
+pub fn is_available() -> bool {
+    let is_set_to = |name: &str, value: &str| {
+        std::env::var_os(name)
+            .map(|variable| variable.to_string_lossy().to_lowercase().contains(value))
+            .unwrap_or(false)
+    };
+
+    !is_set_to("XDG_SESSION_TYPE", "wayland") && !is_set_to("WAYLAND_DISPLAY", "wayland")
+}
+
+/// A connection to the X11 server, kept open to capture screens without reconnecting every time.
+pub struct X11Connection {
+    connection: xcb::Connection,
+    root: Window,
+}
+
+impl X11Connection {
+    /// Connects to the X11 server of the current session.
+    ///
+    /// Only little-endian frame buffers are supported.
+    pub fn connect() -> Result<Self> {
+        let (connection, screen_index) = xcb::Connection::connect(None)?;
+        let setup = connection.get_setup();
+        if setup.image_byte_order() != ImageOrder::LsbFirst {
+            return Err(anyhow!(
+                "Only little-endian X11 frame buffers are supported"
+            ));
+        }
+
+        let root = setup
+            .roots()
+            .nth(screen_index as usize)
+            .ok_or_else(|| anyhow!("Failed to find X11 screen {}", screen_index))?
+            .root();
+
+        Ok(Self { connection, root })
+    }
+
+    /// Captures the area of the root window covered by the given screen as a frame, downsampled
+    /// by the given factor.
+    ///
+    /// Only 24 and 32-bit (BGRX/BGRA) frame buffers are supported.
+    pub fn capture_screen(
+        &self,
+        display_info: &DisplayInfo,
+        downsample_factor: u32,
+    ) -> Result<Frame> {
+        let x = (display_info.x as f32 * display_info.scale_factor) as i16;
+        let y = (display_info.y as f32 * display_info.scale_factor) as i16;
+        let width = (display_info.width as f32 * display_info.scale_factor) as u16;
+        let height = (display_info.height as f32 * display_info.scale_factor) as u16;
+
+        let cookie = self.connection.send_request(&GetImage {
+            format: ImageFormat::ZPixmap,
+            drawable: Drawable::Window(self.root),
+            x,
+            y,
+            width,
+            height,
+            plane_mask: u32::MAX,
+        });
+        let reply = self.connection.wait_for_reply(cookie)?;
+
+        if !matches!(reply.depth(), 24 | 32) {
+            return Err(anyhow!(
+                "Unsupported X11 frame buffer with a depth of {} bits",
+                reply.depth()
+            ));
+        }
+
+        let stride = width as usize * 4;
+        let expected_length = stride * height as usize;
+        if reply.data().len() != expected_length {
+            return Err(anyhow!(
+                "X11 frame buffer of {}x{} pixels has {} bytes, expected {}",
+                width,
+                height,
+                reply.data().len(),
+                expected_length
+            ));
+        }
+
+        convert_raw_pixels(
+            &RawPixels {
+                bytes: reply.data(),
+                width: width as u32,
+                height: height as u32,
+                stride,
+                format: RawPixelFormat::Bgra,
+            },
+            downsample_factor,
+        )
+    }
+}
@@ -5,7 +5,7 @@ use crate::models::colors::{
     HomeAssistantHsbColor, HomeAssistantRgbColor, HomeAssistantRgbbColor, HsvColor, RgbColor,
 };
 
-const MAX_RGB_VALUE: f64 = std::u8::MAX as f64;
+const MAX_RGB_VALUE: f64 = u8::MAX as f64;
 
 pub fn round_float(decimals: u8, value: f64) -> f64 {
     let rounding_carrier = 10_u64.pow(decimals as u32) as f64;
@@ -15,7 +15,7 @@ pub fn round_float(decimals: u8, value: f64) -> f64 {
 pub fn u8_to_f64(component: u8) -> f64 {
     match component {
         0 => 0.0,
-        std::u8::MAX => 1.0,
+        u8::MAX => 1.0,
         _ => component as f64 / MAX_RGB_VALUE,
     }
 }
@@ -24,7 +24,7 @@ pub fn f64_to_u8(component: f64) -> u8 {
     if component <= 0.0 {
         0
     } else if component >= 1.0 {
-        std::u8::MAX
+        u8::MAX
     } else {
         (component * MAX_RGB_VALUE) as u8
     }
@@ -0,0 +1,70 @@
+use crate::{
+    config::ColorSelectionMode,
+    functions::{ToHsv, ToOklch},
+    models::colors::{DominantColor, HsvColor, OklchColor},
+};
+
+/// Returns the hue offsets (in degrees) of the colors of the given harmony mode, relative to the
+/// primary color, or `None` if the mode is not a harmony mode.
+///
+/// The first offset is always 0, so the primary color stays first.
+pub fn harmony_hue_offsets(
+    mode: &ColorSelectionMode,
+    analogous_angle: f64,
+    split_complementary_angle: f64,
+) -> Option<Vec<f64>> {
+    match mode {
+        ColorSelectionMode::Complementary => Some(vec![0.0, 180.0]),
+        ColorSelectionMode::Triadic => Some(vec![0.0, 120.0, 240.0]),
+        ColorSelectionMode::Analogous => Some(vec![0.0, -analogous_angle, analogous_angle]),
+        ColorSelectionMode::SplitComplementary => Some(vec![
+            0.0,
+            180.0 - split_complementary_angle,
+            180.0 + split_complementary_angle,
+        ]),
+        ColorSelectionMode::Default
+        | ColorSelectionMode::Brightness
+        | ColorSelectionMode::HueShift
+        | ColorSelectionMode::Gradient => None,
+    }
+}
+
+/// Replaces the given colors with colors derived from the most dominant (first) color, by
+/// rotating its hue by the given offsets in the OKLCH color space.
+///
+/// Every derived color has the lightness and chroma of the primary color, so they all look
+/// about equally bright and vivid. Every offset is used, even when there are fewer colors than
+/// offsets, and the offsets are repeated until there are `count` colors. Every derived color
+/// takes the place, and thereby the weight, of the dominant color at the same position, or the
+/// weight of the primary color when there is no dominant color at that position.
+pub fn harmonize_colors(
+    colors: Vec<DominantColor<HsvColor>>,
+    hue_offsets: &[f64],
+    count: usize,
+) -> Vec<DominantColor<HsvColor>> {
+    if colors.is_empty() || hue_offsets.is_empty() {
+        return colors;
+    }
+
+    let primary_color = colors[0].color;
+    let primary = primary_color.to_oklch();
+
+    hue_offsets
+        .iter()
+        .cycle()
+        .take(count.max(hue_offsets.len()))
+        .enumerate()
+        .map(|(index, hue_offset)| {
+            // The primary color is kept as is, instead of being converted back from OKLCH.
+            let color = if *hue_offset == 0.0 {
+                primary_color
+            } else {
+                let hue = (primary.h + hue_offset).rem_euclid(360.0);
+                OklchColor::new(primary.l, primary.c, hue).to_hsv()
+            };
+            let weight = colors.get(index).unwrap_or(&colors[0]).weight;
+
+            DominantColor::new(color, weight)
+        })
+        .collect()
+}
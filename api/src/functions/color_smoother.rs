@@ -0,0 +1,162 @@
+use crate::{
+    config::{Smoothing, SmoothingMethod},
+    functions::{oklab_distance, ToHsv, ToOklab},
+    models::colors::{DominantColor, HsvColor, OklabColor},
+};
+
+/// The OKLab distance below which a color is considered to have reached its target.
+const SETTLED_DISTANCE: f64 = 0.002;
+
+/// Smooths the transition between consecutive palettes in the OKLab color space.
+///
+/// Every color of the newest palette is paired with the nearest current color, which then moves
+/// from its current value towards it. That way, colors that only change their position in the
+/// palette don't fade through other colors. When the size of the palette changes, the colors
+/// jump to the new palette at once.
+pub struct ColorSmoother {
+    settings: Smoothing,
+    targets: Vec<DominantColor<HsvColor>>,
+    colors: Vec<SmoothedColor>,
+}
+
+struct SmoothedColor {
+    position: OklabColor,
+    velocity: OklabColor,
+    target: OklabColor,
+}
+
+impl ColorSmoother {
+    pub fn new(settings: Smoothing) -> Self {
+        Self {
+            settings,
+            targets: vec![],
+            colors: vec![],
+        }
+    }
+
+    /// Sets the palette the colors move towards.
+    pub fn set_target(&mut self, targets: &[DominantColor<HsvColor>]) {
+        if targets.len() != self.colors.len() {
+            self.colors = targets
+                .iter()
+                .map(|target| {
+                    let position = target.color.to_oklab();
+                    SmoothedColor {
+                        position,
+                        velocity: OklabColor::new(0.0, 0.0, 0.0),
+                        target: position,
+                    }
+                })
+                .collect();
+        }
+
+        let targets_oklab = targets
+            .iter()
+            .map(|target| target.color.to_oklab())
+            .collect::<Vec<_>>();
+        self.colors = match_to_targets(std::mem::take(&mut self.colors), &targets_oklab);
+        for (color, target) in self.colors.iter_mut().zip(targets_oklab) {
+            color.target = target;
+        }
+        self.targets = targets.to_vec();
+    }
+
+    /// Indicates whether every color has reached its target.
+    pub fn is_settled(&self) -> bool {
+        self.colors.iter().all(|color| {
+            oklab_distance(&color.position, &color.target) < SETTLED_DISTANCE
+                && oklab_distance(&color.velocity, &OklabColor::new(0.0, 0.0, 0.0))
+                    < SETTLED_DISTANCE
+        })
+    }
+
+    /// Moves the colors `elapsed` seconds further towards their targets, and returns the
+    /// resulting palette.
+    ///
+    /// Once the colors have settled, the target palette is returned as is. The weights are
+    /// always those of the target palette.
+    pub fn advance(&mut self, elapsed: f64) -> Vec<DominantColor<HsvColor>> {
+        let elapsed = elapsed.max(0.0);
+        for color in &mut self.colors {
+            match self.settings.method {
+                SmoothingMethod::Ema => {
+                    let share = 1.0 - (-elapsed / self.settings.time_constant).exp();
+                    color.position = lerp(&color.position, &color.target, share);
+                }
+                SmoothingMethod::Spring => step_spring(color, elapsed, self.settings.time_constant),
+            }
+        }
+
+        if self.is_settled() {
+            for color in &mut self.colors {
+                color.position = color.target;
+                color.velocity = OklabColor::new(0.0, 0.0, 0.0);
+            }
+
+            return self.targets.clone();
+        }
+
+        self.colors
+            .iter()
+            .zip(&self.targets)
+            .map(|(color, target)| DominantColor::new(color.position.to_hsv(), target.weight))
+            .collect()
+    }
+}
+
+/// Reorders the given colors so every target is paired with the nearest current color in
+/// OKLab. The closest pairs are matched first. There must be as many colors as targets.
+fn match_to_targets(colors: Vec<SmoothedColor>, targets: &[OklabColor]) -> Vec<SmoothedColor> {
+    let mut pairs = (0..colors.len())
+        .flat_map(|color| (0..targets.len()).map(move |target| (color, target)))
+        .map(|(color, target)| {
+            let distance = oklab_distance(&colors[color].position, &targets[target]);
+            (distance, color, target)
+        })
+        .collect::<Vec<_>>();
+    pairs.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut colors = colors.into_iter().map(Some).collect::<Vec<_>>();
+    let mut matched = targets.iter().map(|_| None).collect::<Vec<_>>();
+    for (_, color, target) in pairs {
+        if matched[target].is_none() && colors[color].is_some() {
+            matched[target] = colors[color].take();
+        }
+    }
+
+    matched.into_iter().flatten().collect()
+}
+
+/// Moves the given color along a critically damped spring, using the exact solution of the
+/// spring's motion so large steps stay stable.
+fn step_spring(color: &mut SmoothedColor, elapsed: f64, time_constant: f64) {
+    // With this angular frequency, the spring covers about 60% of the distance after one time
+    // constant, close to the 63% of the exponential moving average.
+    let omega = 2.0 / time_constant;
+    let decay = (-omega * elapsed).exp();
+
+    let step = |position: f64, velocity: f64, target: f64| {
+        let offset = position - target;
+        let change = velocity + omega * offset;
+
+        (
+            target + (offset + change * elapsed) * decay,
+            (velocity - omega * change * elapsed) * decay,
+        )
+    };
+
+    let (l, velocity_l) = step(color.position.l, color.velocity.l, color.target.l);
+    let (a, velocity_a) = step(color.position.a, color.velocity.a, color.target.a);
+    let (b, velocity_b) = step(color.position.b, color.velocity.b, color.target.b);
+
+    color.position = OklabColor::new(l, a, b);
+    color.velocity = OklabColor::new(velocity_l, velocity_a, velocity_b);
+}
+
+fn lerp(from: &OklabColor, to: &OklabColor, share: f64) -> OklabColor {
+    OklabColor::new(
+        from.l + (to.l - from.l) * share,
+        from.a + (to.a - from.a) * share,
+        from.b + (to.b - from.b) * share,
+    )
+}
@@ -0,0 +1,43 @@
+/// Whether newly calculated colors should be submitted, and why.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SubmissionDecision {
+    /// No colors were submitted yet.
+    First,
+    /// The colors changed by more than the threshold.
+    Changed,
+    /// The colors didn't change by more than the threshold, but the last submission is older
+    /// than the max age.
+    Expired,
+    /// The colors didn't change by more than the threshold.
+    Skip,
+}
+
+impl SubmissionDecision {
+    pub fn should_submit(&self) -> bool {
+        *self != SubmissionDecision::Skip
+    }
+}
+
+/// Decides whether colors should be submitted, given how much they changed since the last
+/// submission and how long ago (in seconds) that submission was.
+///
+/// `change` is `None` if nothing was submitted yet. Palettes of different sizes have an infinite
+/// difference (see `max_color_difference`), so they are always submitted. Without a max age,
+/// colors are never submitted again just because of their age.
+pub fn decide_submission(
+    change: Option<f64>,
+    color_change_threshold: f64,
+    last_submission_age: f64,
+    max_submission_age: Option<f64>,
+) -> SubmissionDecision {
+    let is_expired = max_submission_age
+        .map(|max_age| last_submission_age >= max_age)
+        .unwrap_or(false);
+
+    match change {
+        None => SubmissionDecision::First,
+        Some(change) if change > color_change_threshold => SubmissionDecision::Changed,
+        Some(_) if is_expired => SubmissionDecision::Expired,
+        Some(_) => SubmissionDecision::Skip,
+    }
+}
@@ -0,0 +1,54 @@
+use crate::{
+    functions::{ciede2000, ToLab},
+    models::colors::{DominantColor, LabColor},
+};
+
+/// Merges colors that are perceptually (almost) identical, and returns at most `max_count`
+/// distinct colors, ordered from most to least dominant.
+///
+/// The candidates are expected to be ordered from most to least dominant. Every candidate
+/// closer than `min_distance` (in CIEDE2000 units) to a more dominant color is merged into the
+/// closest of those colors, adding its weight to that color's weight. The remaining candidates
+/// are kept in order until `max_count` colors are kept, so passing more candidates than
+/// `max_count` fills the places of merged colors with the next most dominant distinct colors.
+/// Candidates that no longer fit are merged into the closest kept color, however far away it is,
+/// so the weights of the kept colors add up to the weights of all candidates.
+pub fn deduplicate_colors(
+    candidates: Vec<DominantColor>,
+    min_distance: f64,
+    max_count: usize,
+) -> Vec<DominantColor> {
+    let mut colors: Vec<(DominantColor, LabColor)> = Vec::with_capacity(max_count);
+
+    for candidate in candidates {
+        let lab = candidate.color.to_lab();
+
+        let closest = colors
+            .iter()
+            .enumerate()
+            .map(|(index, (_, color_lab))| (index, ciede2000(color_lab, &lab)))
+            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+        match closest {
+            Some((index, distance)) if distance < min_distance || colors.len() >= max_count => {
+                colors[index].0.weight += candidate.weight
+            }
+            _ if colors.len() < max_count => colors.push((candidate, lab)),
+            _ => {}
+        }
+    }
+
+    let mut colors = colors
+        .into_iter()
+        .map(|(color, _)| color)
+        .collect::<Vec<_>>();
+    // Merging can make a color more dominant than the colors before it. The sort is stable, so
+    // colors of equal weight keep their order.
+    colors.sort_by(|a, b| {
+        b.weight
+            .partial_cmp(&a.weight)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    colors
+}
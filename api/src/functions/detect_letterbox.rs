@@ -0,0 +1,166 @@
+use crate::models::{
+    colors::RgbColor,
+    frame::{Frame, Rect},
+};
+
+/// The highest value any RGB component of a pixel can have for it to be part of a black bar.
+const BAR_MAX_COMPONENT_VALUE: u8 = 24;
+/// The fraction of pixels in a row or column that must be dark for it to be part of a black bar.
+const BAR_MIN_DARK_FRACTION: f64 = 0.98;
+/// The minimum size of a black bar, as a fraction of the frame's height or width.
+const BAR_MIN_FRACTION: f64 = 0.02;
+/// The minimum size of the picture area, as a fraction of the frame's height or width. Frames
+/// with a smaller picture area (like a black screen) are considered to be dark, not letterboxed.
+const PICTURE_MIN_FRACTION: f64 = 0.25;
+/// The maximum difference between two picture areas, as a fraction of the frame's height or
+/// width, for them to be considered the same.
+const PICTURE_TOLERANCE_FRACTION: f64 = 0.01;
+
+pub const DEFAULT_LETTERBOX_STABLE_CAPTURES: u32 = 3;
+
+/// Detects black bars around the picture in a series of frames, such as those around a film
+/// played on a screen with a different aspect ratio (letterboxing and pillarboxing).
+///
+/// To prevent flickering, a newly detected picture area is only used once it has been detected
+/// in several consecutive captures.
+#[derive(Debug, Clone)]
+pub struct LetterboxDetector {
+    stable_captures: u32,
+    frame_size: (u32, u32),
+    picture_area: Option<Rect>,
+    candidate: Option<(Rect, u32)>,
+}
+
+impl LetterboxDetector {
+    /// Creates a detector that only uses a newly detected picture area once it has been detected
+    /// in `stable_captures` consecutive captures.
+    pub fn new(stable_captures: u32) -> Self {
+        Self {
+            stable_captures: stable_captures.max(1),
+            frame_size: (0, 0),
+            picture_area: None,
+            candidate: None,
+        }
+    }
+
+    /// The picture area currently in use, or `None` if no black bars are detected.
+    pub fn picture_area(&self) -> Option<Rect> {
+        self.picture_area
+    }
+
+    /// Detects the black bars in the given frame and returns the picture area to use, or `None`
+    /// if the entire frame should be used.
+    pub fn update(&mut self, frame: &Frame) -> Option<Rect> {
+        if self.frame_size != (frame.width, frame.height) {
+            self.frame_size = (frame.width, frame.height);
+            self.picture_area = None;
+            self.candidate = None;
+        }
+
+        let full_frame = Rect::new(0, 0, frame.width, frame.height);
+        let detected_area = match detect_picture_area(frame) {
+            Some(picture_area) => picture_area,
+            // The frame is (almost) entirely dark, so nothing can be said about the black bars.
+            None => return self.picture_area,
+        };
+
+        let current_area = self.picture_area.unwrap_or(full_frame);
+        if is_similar(frame, &detected_area, &current_area) {
+            self.candidate = None;
+            return self.picture_area;
+        }
+
+        let captures = match self.candidate {
+            Some((candidate, captures)) if is_similar(frame, &detected_area, &candidate) => {
+                captures + 1
+            }
+            _ => 1,
+        };
+
+        if captures >= self.stable_captures {
+            self.picture_area = Some(detected_area).filter(|area| area != &full_frame);
+            self.candidate = None;
+        } else {
+            self.candidate = Some((detected_area, captures));
+        }
+
+        self.picture_area
+    }
+}
+
+impl Default for LetterboxDetector {
+    fn default() -> Self {
+        Self::new(DEFAULT_LETTERBOX_STABLE_CAPTURES)
+    }
+}
+
+/// Detects the area of the given frame that is not covered by black bars.
+///
+/// Since black bars are symmetrical, the smallest of two opposite bars is used for both (which
+/// also prevents subtitles in a black bar from being seen as part of the picture). Returns
+/// `None` if the frame is (almost) entirely dark.
+pub fn detect_picture_area(frame: &Frame) -> Option<Rect> {
+    let (width, height) = (frame.width, frame.height);
+    if width == 0 || height == 0 {
+        return None;
+    }
+
+    let is_dark_row =
+        |y: u32| is_dark_line((0..width).map(|x| frame.pixels[(y * width + x) as usize]));
+    let is_dark_column =
+        |x: u32| is_dark_line((0..height).map(|y| frame.pixels[(y * width + x) as usize]));
+
+    let top = (0..height).take_while(|y| is_dark_row(*y)).count() as u32;
+    if top == height {
+        return None;
+    }
+    let bottom = (0..height).rev().take_while(|y| is_dark_row(*y)).count() as u32;
+    let left = (0..width).take_while(|x| is_dark_column(*x)).count() as u32;
+    let right = (0..width).rev().take_while(|x| is_dark_column(*x)).count() as u32;
+
+    let vertical_bar = bar_size(top.min(bottom), height);
+    let horizontal_bar = bar_size(left.min(right), width);
+
+    let picture_height = height - vertical_bar * 2;
+    let picture_width = width - horizontal_bar * 2;
+    if (picture_height as f64) < height as f64 * PICTURE_MIN_FRACTION
+        || (picture_width as f64) < width as f64 * PICTURE_MIN_FRACTION
+    {
+        return None;
+    }
+
+    Some(Rect::new(
+        horizontal_bar,
+        vertical_bar,
+        picture_width,
+        picture_height,
+    ))
+}
+
+/// Returns the given bar size, or 0 if it is too small to be a black bar.
+fn bar_size(size: u32, total: u32) -> u32 {
+    if (size as f64) < total as f64 * BAR_MIN_FRACTION {
+        0
+    } else {
+        size
+    }
+}
+
+fn is_dark_line(pixels: impl ExactSizeIterator<Item = RgbColor>) -> bool {
+    let pixel_count = pixels.len();
+    let dark_count = pixels
+        .filter(|pixel| pixel.red().max(pixel.green()).max(pixel.blue()) <= BAR_MAX_COMPONENT_VALUE)
+        .count();
+
+    dark_count as f64 >= pixel_count as f64 * BAR_MIN_DARK_FRACTION
+}
+
+fn is_similar(frame: &Frame, a: &Rect, b: &Rect) -> bool {
+    let horizontal_tolerance = (frame.width as f64 * PICTURE_TOLERANCE_FRACTION).ceil() as u32;
+    let vertical_tolerance = (frame.height as f64 * PICTURE_TOLERANCE_FRACTION).ceil() as u32;
+
+    a.x.abs_diff(b.x) <= horizontal_tolerance
+        && a.width.abs_diff(b.width) <= horizontal_tolerance
+        && a.y.abs_diff(b.y) <= vertical_tolerance
+        && a.height.abs_diff(b.height) <= vertical_tolerance
+}
@@ -0,0 +1,135 @@
+use crate::{
+    config::{Downsampling, DownsamplingMethod},
+    models::{colors::RgbColor, frame::Frame},
+};
+
+/// Shrinks the given frame to fit within the pixel budget of the given downsampling.
+///
+/// Excluded pixels are never sampled. With the box method, a pixel of the resulting frame is
+/// only excluded if every pixel in its block is excluded.
+pub fn downsample_frame(frame: &Frame, downsampling: &Downsampling) -> Frame {
+    let factor = downsampling.factor(frame.width, frame.height);
+    if factor <= 1 {
+        return frame.clone();
+    }
+
+    match downsampling.method {
+        DownsamplingMethod::Stride => stride_sample(frame, factor),
+        DownsamplingMethod::Box => box_filter(frame, factor),
+    }
+}
+
+/// Downsamples the given frame by the given factor with the box method, multiplying its `scale`
+/// by the factor.
+pub(crate) fn box_downsample(frame: &Frame, factor: u32) -> Frame {
+    let mut downsampled = box_filter(frame, factor);
+    downsampled.scale = frame.scale.saturating_mul(factor);
+
+    downsampled
+}
+
+fn stride_sample(frame: &Frame, factor: u32) -> Frame {
+    let (width, height) = (frame.width.div_ceil(factor), frame.height.div_ceil(factor));
+    let source_index = |x: u32, y: u32| (y * factor * frame.width + x * factor) as usize;
+
+    let pixels = (0..height)
+        .flat_map(|y| (0..width).map(move |x| frame.pixels[source_index(x, y)]))
+        .collect();
+
+    let mut downsampled = Frame::new(width, height, pixels);
+    if frame.excluded.is_some() {
+        downsampled.exclude_where(|x, y| frame.is_excluded(source_index(x, y)));
+    }
+
+    downsampled
+}
+
+fn box_filter(frame: &Frame, factor: u32) -> Frame {
+    let (width, height) = (frame.width.div_ceil(factor), frame.height.div_ceil(factor));
+    let mut averager = BlockAverager::new(width as usize);
+    let mut averages = Vec::with_capacity((width * height) as usize);
+
+    for y in 0..height {
+        for source_y in y * factor..((y + 1) * factor).min(frame.height) {
+            for source_x in 0..frame.width {
+                let index = (source_y * frame.width + source_x) as usize;
+                if !frame.is_excluded(index) {
+                    let pixel = frame.pixels[index];
+                    averager.add(
+                        (source_x / factor) as usize,
+                        [pixel.red(), pixel.green(), pixel.blue()],
+                    );
+                }
+            }
+        }
+
+        averager.finish_row(&mut averages);
+    }
+
+    let pixels = averages
+        .iter()
+        .map(|average| average.unwrap_or(RgbColor::new(0, 0, 0)))
+        .collect();
+    let mut downsampled = Frame::new(width, height, pixels);
+    if averages.contains(&None) {
+        downsampled.exclude_where(|x, y| averages[(y * width + x) as usize].is_none());
+    }
+
+    downsampled
+}
+
+/// Averages blocks of pixels, one row of blocks at a time.
+///
+/// Pixels are added to the block they belong to, after which `finish_row` appends the average
+/// of every block and starts the next row.
+///
+/// The sums are 64-bit, as a block of a large frame with a small pixel budget can hold more
+/// than 16 million pixels, whose channels would overflow a 32-bit sum.
+pub(crate) struct BlockAverager {
+    sums: Vec<[u64; 4]>,
+}
+
+impl BlockAverager {
+    pub(crate) fn new(blocks_per_row: usize) -> Self {
+        Self {
+            sums: vec![[0; 4]; blocks_per_row],
+        }
+    }
+
+    /// Adds the channels of a pixel to the given block. The channels can be in any order, as
+    /// long as it is the same for every pixel.
+    #[inline]
+    pub(crate) fn add(&mut self, block: usize, channels: [u8; 3]) {
+        let sum = &mut self.sums[block];
+        sum[0] += channels[0] as u64;
+        sum[1] += channels[1] as u64;
+        sum[2] += channels[2] as u64;
+        sum[3] += 1;
+    }
+
+    /// Appends the averages of the current row of blocks, with `None` for blocks without any
+    /// pixels, and resets the blocks for the next row. `order` gives the index of the red, green
+    /// and blue channel in the added channels.
+    pub(crate) fn finish_row_ordered(
+        &mut self,
+        averages: &mut Vec<Option<RgbColor>>,
+        [red, green, blue]: [usize; 3],
+    ) {
+        averages.extend(self.sums.iter().map(|sum| {
+            let count = sum[3];
+            (count > 0).then(|| {
+                RgbColor::new(
+                    (sum[red] / count) as u8,
+                    (sum[green] / count) as u8,
+                    (sum[blue] / count) as u8,
+                )
+            })
+        }));
+        self.sums.iter_mut().for_each(|sum| *sum = [0; 4]);
+    }
+
+    /// Like `finish_row_ordered`, for channels added in RGB order.
+    pub(crate) fn finish_row(&mut self, averages: &mut Vec<Option<RgbColor>>) {
+        self.finish_row_ordered(averages, [0, 1, 2]);
+    }
+}
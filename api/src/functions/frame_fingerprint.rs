@@ -0,0 +1,80 @@
+use crate::models::frame::Frame;
+
+/// The width and height of the thumbnail a fingerprint is made of.
+const THUMBNAIL_SIZE: u32 = 16;
+/// The maximum amount of pixels sampled per cell of the thumbnail, along each axis.
+const MAX_CELL_SAMPLES: u32 = 16;
+
+/// A tiny thumbnail of a frame, used to cheaply tell whether two frames look different.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameFingerprint {
+    frame_size: (u32, u32),
+    thumbnail: Vec<[u8; 3]>,
+}
+
+impl FrameFingerprint {
+    /// Creates the fingerprint of the given frame, by averaging the included pixels of every
+    /// cell of a 16 by 16 grid laid over it.
+    ///
+    /// Large frames are sampled on an evenly spaced grid of pixels, so creating a fingerprint
+    /// stays cheap regardless of the size of the frame.
+    pub fn new(frame: &Frame) -> Self {
+        let cell_count = (THUMBNAIL_SIZE * THUMBNAIL_SIZE) as usize;
+        let mut sums = vec![[0u64; 4]; cell_count];
+
+        let step = |length: u32| (length / (THUMBNAIL_SIZE * MAX_CELL_SAMPLES)).max(1) as usize;
+        let sampled_x = (0..frame.width)
+            .step_by(step(frame.width))
+            .map(|x| (x, x * THUMBNAIL_SIZE / frame.width))
+            .collect::<Vec<_>>();
+        for y in (0..frame.height).step_by(step(frame.height)) {
+            let row_offset = y * THUMBNAIL_SIZE / frame.height * THUMBNAIL_SIZE;
+            for (x, cell_x) in &sampled_x {
+                let index = (y * frame.width + x) as usize;
+                if frame.is_excluded(index) {
+                    continue;
+                }
+
+                let pixel = frame.pixels[index];
+                let sum = &mut sums[(row_offset + cell_x) as usize];
+                sum[0] += pixel.red() as u64;
+                sum[1] += pixel.green() as u64;
+                sum[2] += pixel.blue() as u64;
+                sum[3] += 1;
+            }
+        }
+
+        let thumbnail = sums
+            .iter()
+            .map(|[red, green, blue, count]| match count {
+                0 => [0, 0, 0],
+                count => [red / count, green / count, blue / count].map(|value| value as u8),
+            })
+            .collect();
+
+        Self {
+            frame_size: (frame.width, frame.height),
+            thumbnail,
+        }
+    }
+
+    /// Returns how different the frames of this and the given fingerprint look, from 0 (the same)
+    /// to 1 (completely different).
+    ///
+    /// This is the average difference of all color channels of the thumbnails. Frames of
+    /// different sizes are always completely different.
+    pub fn difference(&self, other: &FrameFingerprint) -> f64 {
+        if self.frame_size != other.frame_size {
+            return 1.0;
+        }
+
+        let total_difference = self
+            .thumbnail
+            .iter()
+            .zip(&other.thumbnail)
+            .flat_map(|(a, b)| a.iter().zip(b).map(|(a, b)| a.abs_diff(*b) as u64))
+            .sum::<u64>();
+
+        total_difference as f64 / (self.thumbnail.len() * 3 * u8::MAX as usize) as f64
+    }
+}
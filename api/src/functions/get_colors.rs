@@ -1,23 +1,75 @@
 use crate::config::ColorSelectionMode;
 use crate::config::DesktopDyeConfig;
+use crate::config::GradientSettings;
 use crate::functions::*;
-use crate::models::colors::HsvColor;
+use crate::models::colors::{DominantColor, HsvColor};
+use crate::models::frame::Frame;
 use angular_units::Deg;
 use anyhow::*;
 use colored::Colorize;
 use prisma::Hsv;
-use screenshots::Screen;
 
 const BRIGHTNESS_THRESHOLD: f64 = 0.80;
 
-pub async fn get_colors_from_screen(
+pub async fn get_colors_from_source(
     config: &DesktopDyeConfig,
-    screen: &Screen,
-) -> Result<Vec<HsvColor>> {
-    let pixels = capture_pixels(screen).context("Failed to capture screen")?;
+    registry: &PaletteExtractorRegistry,
+    source: &mut dyn PixelSource,
+) -> Result<Vec<DominantColor<HsvColor>>> {
+    let frame = capture_frame(config, source)?;
 
-    let dominant_colors =
-        calculate_dominant_colors(&pixels, &config.algorithm, &config.sample_size);
+    get_colors_from_frame(config, registry, &frame).await
+}
+
+/// Calculates the dominant colors of the given frame, using the extractor registered in the
+/// given registry for the configured algorithm.
+pub async fn get_colors_from_frame(
+    config: &DesktopDyeConfig,
+    registry: &PaletteExtractorRegistry,
+    frame: &Frame,
+) -> Result<Vec<DominantColor<HsvColor>>> {
+    let dominant_colors = match &config.edge_zones {
+        // Every zone covers the same share of the frame.
+        Some(edge_zones) => {
+            let colors = calculate_edge_zone_colors(frame, edge_zones, config, registry)?;
+            let weight = 1.0 / colors.len().max(1) as f64;
+
+            colors
+                .into_iter()
+                .map(|color| DominantColor::new(color, weight))
+                .collect()
+        }
+        None => {
+            let pixels = match &config.downsampling {
+                Some(downsampling) => downsample_frame(frame, downsampling).included_pixels(),
+                None => frame.included_pixels(),
+            };
+            if pixels.is_empty() {
+                return Err(anyhow!(
+                    "All pixels of the captured frame are excluded, cannot calculate colors"
+                ));
+            }
+
+            match &config.deduplication {
+                Some(deduplication) => deduplicate_colors(
+                    calculate_dominant_colors(
+                        &pixels,
+                        registry,
+                        &config.algorithm,
+                        &deduplication.candidate_count(config.sample_size),
+                    )?,
+                    deduplication.min_distance,
+                    config.sample_size as usize,
+                ),
+                None => calculate_dominant_colors(
+                    &pixels,
+                    registry,
+                    &config.algorithm,
+                    &config.sample_size,
+                )?,
+            }
+        }
+    };
     if dominant_colors.is_empty() {
         return Err(anyhow!(
             "Failed to calculate dominant colors, got 0 results"
@@ -26,17 +78,12 @@ pub async fn get_colors_from_screen(
 
     let dominant_colors = dominant_colors
         .into_iter()
-        .map(|color| {
-            let mut hsv = color.to_hsv();
-
-            hsv.set_saturation((hsv.saturation() + 0.2).min(1.0));
-            hsv.set_value((hsv.value() + 0.2).min(1.0));
-
-            hsv
+        .map(|dominant_color| {
+            dominant_color.map(|color| grade_color(color.to_hsv(), &config.color_grading))
         })
         .collect::<Vec<_>>();
 
-    let most_dominant_color = dominant_colors[0];
+    let most_dominant_color = dominant_colors[0].color;
     println!(
         "Dominant color: {}",
         format!("#{}", most_dominant_color.to_hex_value())
@@ -47,42 +94,54 @@ pub async fn get_colors_from_screen(
                 most_dominant_color.to_rgb().green(),
                 most_dominant_color.to_rgb().blue()
             )
-            .to_string()
     );
 
+    // In edge zone mode, every color belongs to a specific zone, so the colors are never
+    // reordered or replaced.
+    let mode = match config.edge_zones {
+        Some(_) => &ColorSelectionMode::Default,
+        None => &config.mode,
+    };
+
     Ok(apply_color_correction(
         dominant_colors,
-        &config.mode,
+        mode,
         &config.hue_shift,
+        harmony_hue_offsets(
+            mode,
+            config.analogous_angle,
+            config.split_complementary_angle,
+        ),
+        config.sample_size as usize,
+        &config.gradient,
         &config.brightness_factor,
     ))
 }
 
 fn apply_color_correction(
-    colors: Vec<HsvColor>,
+    colors: Vec<DominantColor<HsvColor>>,
     mode: &ColorSelectionMode,
     hue_shift: &f64,
+    hue_offsets: Option<Vec<f64>>,
+    sample_size: usize,
+    gradient: &GradientSettings,
     brightness_factor: &f64,
-) -> Vec<HsvColor> {
+) -> Vec<DominantColor<HsvColor>> {
     let mode_adjusted_colors = match mode {
         crate::config::ColorSelectionMode::Default => colors,
         crate::config::ColorSelectionMode::Brightness => {
             let bright_colors = colors
                 .iter()
-                .filter(|color| color.value() > BRIGHTNESS_THRESHOLD)
+                .filter(|dominant_color| dominant_color.color.value() > BRIGHTNESS_THRESHOLD)
                 .cloned()
                 .collect::<Vec<_>>();
 
-            let primary_color = bright_colors
-                .first()
-                .map(|color| color.clone())
-                .unwrap_or_else(|| {
-                    let colors = &mut colors.clone();
-                    colors.sort_by(|a, b| b.value().partial_cmp(&a.value()).unwrap());
+            let primary_color = bright_colors.first().copied().unwrap_or_else(|| {
+                let colors = &mut colors.clone();
+                colors.sort_by(|a, b| b.color.value().partial_cmp(&a.color.value()).unwrap());
 
-                    colors[0]
-                })
-                .clone();
+                colors[0]
+            });
 
             let mut final_colors = vec![primary_color];
             final_colors.extend(
@@ -95,7 +154,7 @@ fn apply_color_correction(
             final_colors
         }
         crate::config::ColorSelectionMode::HueShift => {
-            let primary_hsv = colors[0];
+            let primary_hsv = colors[0].color;
 
             let colors_len = colors.len();
             if colors_len == 1 {
@@ -107,7 +166,7 @@ fn apply_color_correction(
 
             let mut final_colors = vec![];
 
-            for i in 0..colors_len {
+            for (i, dominant_color) in colors.iter().enumerate() {
                 let hue = lower_hue + hue_step * i as f64;
                 let hue = if hue < 0.0 {
                     hue + 360.0
@@ -119,11 +178,20 @@ fn apply_color_correction(
 
                 let hsv = Hsv::new(Deg(hue), primary_hsv.saturation(), primary_hsv.value());
 
-                final_colors.push(hsv);
+                // Every generated color takes the place, and thereby the weight, of the
+                // dominant color at the same position.
+                final_colors.push(DominantColor::new(hsv, dominant_color.weight));
             }
 
             final_colors
         }
+        crate::config::ColorSelectionMode::Complementary
+        | crate::config::ColorSelectionMode::Triadic
+        | crate::config::ColorSelectionMode::Analogous
+        | crate::config::ColorSelectionMode::SplitComplementary => {
+            harmonize_colors(colors, &hue_offsets.unwrap_or_default(), sample_size)
+        }
+        crate::config::ColorSelectionMode::Gradient => interpolate_gradient(&colors, gradient),
     };
 
     if brightness_factor == &1.0 {
@@ -132,10 +200,11 @@ fn apply_color_correction(
 
     mode_adjusted_colors
         .into_iter()
-        .map(|color| {
-            let mut hsv = color;
-            hsv.set_value((hsv.value() * brightness_factor).min(1.0));
-            hsv
+        .map(|dominant_color| {
+            dominant_color.map(|mut hsv| {
+                hsv.set_value((hsv.value() * brightness_factor).min(1.0));
+                hsv
+            })
         })
         .collect()
 }
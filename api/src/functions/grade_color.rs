@@ -0,0 +1,45 @@
+use prisma::{FromColor, Rgb};
+
+use crate::{config::ColorGradingStep, models::colors::HsvColor};
+
+/// Applies every step of the given color grading pipeline to the given color, in order.
+pub fn grade_color(color: HsvColor, steps: &[ColorGradingStep]) -> HsvColor {
+    steps.iter().fold(color, apply_grading_step)
+}
+
+/// Applies a single color grading step to the given color.
+///
+/// The saturation and brightness of the result are always kept between 0 and 1.
+pub fn apply_grading_step(color: HsvColor, step: &ColorGradingStep) -> HsvColor {
+    let mut hsv = color;
+
+    match step {
+        ColorGradingStep::Saturation { gain, offset } => {
+            hsv.set_saturation((hsv.saturation() * gain + offset).clamp(0.0, 1.0));
+        }
+        ColorGradingStep::Value { gain, offset } => {
+            hsv.set_value((hsv.value() * gain + offset).clamp(0.0, 1.0));
+        }
+        ColorGradingStep::Gamma { gamma } => {
+            hsv.set_value(hsv.value().powf(1.0 / gamma).clamp(0.0, 1.0));
+        }
+        ColorGradingStep::Contrast { amount } => {
+            hsv.set_value(((hsv.value() - 0.5) * amount + 0.5).clamp(0.0, 1.0));
+        }
+        ColorGradingStep::BrightnessClamp { min, max } => {
+            hsv.set_value(hsv.value().clamp(*min, *max));
+        }
+        ColorGradingStep::WhiteBalance { red, green, blue } => {
+            let rgb = Rgb::<f64>::from_color(&hsv);
+            let balanced = Rgb::new(
+                (rgb.red() * red).clamp(0.0, 1.0),
+                (rgb.green() * green).clamp(0.0, 1.0),
+                (rgb.blue() * blue).clamp(0.0, 1.0),
+            );
+
+            hsv = HsvColor::from_color(&balanced);
+        }
+    }
+
+    hsv
+}
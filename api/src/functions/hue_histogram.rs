@@ -0,0 +1,98 @@
+use prisma::Rgb;
+
+use crate::{
+    config::HueHistogramSettings,
+    functions::{count_unique_colors, ToHsv},
+    models::colors::RgbColor,
+};
+
+/// Selects (at most) `k` colors by binning the given pixels by hue, favouring vivid colors over
+/// large areas of gray, and returns every color along with its bin's share of the total weight.
+///
+/// Pixels below the minimum saturation or brightness are ignored. Every other pixel adds its
+/// saturation times its brightness to the weight of its hue bin, so a small area of a vivid
+/// color can outweigh a large area of a dull one. The colors of the `k` heaviest bins are
+/// returned (each being the weighted average of the pixels in the bin), ordered by weight from
+/// heaviest to lightest.
+///
+/// The returned weights are the bins' shares of the total weight of all bins, so they reflect
+/// how vivid and how large every color is, rather than the share of pixels it covers.
+///
+/// If every pixel is ignored, the average color of all pixels is returned with a weight of 1.
+pub fn hue_histogram(
+    pixels: &[RgbColor],
+    k: usize,
+    settings: &HueHistogramSettings,
+) -> Vec<(RgbColor, f64)> {
+    if k == 0 || pixels.is_empty() {
+        return vec![];
+    }
+
+    let bin_count = settings.bins.max(1) as usize;
+    // The weight and weighted sum of every color channel of every bin.
+    let mut bins = vec![[0.0; 4]; bin_count];
+
+    let unique_colors = count_unique_colors(pixels);
+    for (rgb, count) in &unique_colors {
+        let hsv = Rgb::new(rgb[0], rgb[1], rgb[2]).to_hsv();
+        if hsv.saturation() < settings.min_saturation || hsv.value() < settings.min_value {
+            continue;
+        }
+
+        let bin = ((hsv.hue().0 / 360.0 * bin_count as f64) as usize).min(bin_count - 1);
+        let weight = *count as f64 * hsv.saturation() * hsv.value();
+
+        let sums = &mut bins[bin];
+        sums[0] += weight;
+        sums[1] += rgb[0] as f64 * weight;
+        sums[2] += rgb[1] as f64 * weight;
+        sums[3] += rgb[2] as f64 * weight;
+    }
+
+    let mut colors = bins
+        .into_iter()
+        .filter(|[weight, ..]| *weight > 0.0)
+        .map(|[weight, red, green, blue]| {
+            let color = Rgb::new(
+                (red / weight).round() as u8,
+                (green / weight).round() as u8,
+                (blue / weight).round() as u8,
+            );
+
+            (color, weight)
+        })
+        .collect::<Vec<_>>();
+
+    if colors.is_empty() {
+        return vec![(average_color(&unique_colors), 1.0)];
+    }
+
+    let total_weight = colors.iter().map(|(_, weight)| weight).sum::<f64>();
+    for (_, weight) in &mut colors {
+        *weight /= total_weight;
+    }
+
+    // The sort is stable, so bins of equal weight stay ordered by hue.
+    colors.sort_by(|(_, weight_a), (_, weight_b)| {
+        weight_b
+            .partial_cmp(weight_a)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    colors.truncate(k);
+
+    colors
+}
+
+fn average_color(unique_colors: &[([u8; 3], usize)]) -> RgbColor {
+    let mut sums = [0u64; 3];
+    let mut population = 0u64;
+    for (rgb, count) in unique_colors {
+        for (sum, value) in sums.iter_mut().zip(rgb) {
+            *sum += *value as u64 * *count as u64;
+        }
+        population += *count as u64;
+    }
+
+    let [red, green, blue] = sums.map(|sum| (sum / population.max(1)) as u8);
+    Rgb::new(red, green, blue)
+}
@@ -0,0 +1,144 @@
+use prisma::Rgb;
+
+use crate::{
+    config::{GradientColorSpace, GradientSettings},
+    functions::{u8_to_f64, ToHsv, ToOklab, ToOklch, ToRgb},
+    models::colors::{DominantColor, HsvColor, OklabColor, OklchColor},
+};
+
+/// Colors with a lower OKLCH chroma look (almost) gray, so their hue is meaningless.
+const ACHROMATIC_CHROMA: f64 = 0.03;
+
+/// Creates a gradient of `settings.count` evenly spaced colors that passes through all of the
+/// given dominant colors.
+///
+/// The dominant colors are first ordered to minimize the hue jumps between them (see
+/// [`order_by_hue`]), and then interpolated in the configured color space. Every color of the
+/// gradient gets the same weight.
+pub fn interpolate_gradient(
+    colors: &[DominantColor<HsvColor>],
+    settings: &GradientSettings,
+) -> Vec<DominantColor<HsvColor>> {
+    let stops = order_by_hue(
+        &colors
+            .iter()
+            .map(|dominant_color| dominant_color.color)
+            .collect::<Vec<_>>(),
+    );
+    if stops.is_empty() {
+        return vec![];
+    }
+
+    let count = settings.count.max(1) as usize;
+    let weight = 1.0 / count as f64;
+    let last_segment = stops.len().saturating_sub(2);
+
+    (0..count)
+        .map(|i| {
+            let color = if stops.len() == 1 {
+                stops[0]
+            } else {
+                // The position along the gradient, in stops.
+                let position = i as f64 / (count - 1).max(1) as f64 * (stops.len() - 1) as f64;
+                let segment = (position.floor() as usize).min(last_segment);
+
+                interpolate(
+                    &stops[segment],
+                    &stops[segment + 1],
+                    position - segment as f64,
+                    settings.color_space,
+                )
+            };
+
+            DominantColor::new(color, weight)
+        })
+        .collect()
+}
+
+/// Orders the given colors by their OKLCH hue, such that the gradient through them covers as
+/// little of the color wheel as possible.
+///
+/// The colors are placed around the color wheel, which is cut open at the largest hue gap
+/// between two colors. Colors that look gray have no meaningful hue, so they are placed after
+/// the other colors, in their original order.
+pub fn order_by_hue(colors: &[HsvColor]) -> Vec<HsvColor> {
+    let (mut chromatic, achromatic): (Vec<_>, Vec<_>) = colors
+        .iter()
+        .map(|color| (*color, color.to_oklch()))
+        .partition(|(_, oklch)| oklch.c >= ACHROMATIC_CHROMA);
+
+    chromatic.sort_by(|(_, a), (_, b)| a.h.partial_cmp(&b.h).unwrap_or(std::cmp::Ordering::Equal));
+
+    // The gap before every color, from the previous color around the color wheel.
+    let largest_gap_end = (0..chromatic.len())
+        .map(|i| {
+            let previous = &chromatic[(i + chromatic.len() - 1) % chromatic.len()].1;
+            (i, (chromatic[i].1.h - previous.h).rem_euclid(360.0))
+        })
+        .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(i, _)| i)
+        .unwrap_or(0);
+    chromatic.rotate_left(largest_gap_end);
+
+    chromatic
+        .into_iter()
+        .chain(achromatic)
+        .map(|(color, _)| color)
+        .collect()
+}
+
+/// Returns the color `share` (from 0 to 1) of the way from `from` to `to`, in the given color
+/// space.
+fn interpolate(
+    from: &HsvColor,
+    to: &HsvColor,
+    share: f64,
+    color_space: GradientColorSpace,
+) -> HsvColor {
+    // Keep the dominant colors as they are, instead of converting them back and forth.
+    if share <= 0.0 {
+        return *from;
+    } else if share >= 1.0 {
+        return *to;
+    }
+
+    let lerp = |from: f64, to: f64| from + (to - from) * share;
+
+    match color_space {
+        GradientColorSpace::Oklab => {
+            let (from, to) = (from.to_oklab(), to.to_oklab());
+
+            OklabColor::new(lerp(from.l, to.l), lerp(from.a, to.a), lerp(from.b, to.b)).to_hsv()
+        }
+        GradientColorSpace::Oklch => {
+            let (from, to) = (from.to_oklch(), to.to_oklch());
+            // A gray color takes the hue of the other color, so the hue doesn't sweep past
+            // unrelated colors on the way.
+            let (from_hue, to_hue) = match (from.c < ACHROMATIC_CHROMA, to.c < ACHROMATIC_CHROMA) {
+                (true, false) => (to.h, to.h),
+                (false, true) => (from.h, from.h),
+                _ => (from.h, to.h),
+            };
+            // Take the shortest way around the color wheel.
+            let hue_change = (to_hue - from_hue + 180.0).rem_euclid(360.0) - 180.0;
+
+            OklchColor::new(
+                lerp(from.l, to.l),
+                lerp(from.c, to.c),
+                (from_hue + hue_change * share).rem_euclid(360.0),
+            )
+            .to_hsv()
+        }
+        GradientColorSpace::Rgb => {
+            let (from, to) = (from.to_rgb(), to.to_rgb());
+            let channel = |from: u8, to: u8| lerp(u8_to_f64(from), u8_to_f64(to));
+
+            Rgb::new(
+                channel(from.red(), to.red()),
+                channel(from.green(), to.green()),
+                channel(from.blue(), to.blue()),
+            )
+            .to_hsv()
+        }
+    }
+}
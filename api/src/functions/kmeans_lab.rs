@@ -0,0 +1,190 @@
+use crate::{
+    config::KMeansSettings,
+    functions::{count_unique_colors, ToLab, ToRgb},
+    models::colors::{LabColor, RgbColor},
+};
+
+/// Clusters the given pixels into (at most) `k` colors using k-means in the CIELAB color space,
+/// and returns the center of every cluster along with the amount of pixels in it.
+///
+/// The initial centers are picked using k-means++, driven by a pseudo-random number generator
+/// seeded with the configured seed, so the same pixels and settings always result in the same
+/// colors. The colors are ordered by population, from largest to smallest.
+pub fn kmeans_lab(
+    pixels: &[RgbColor],
+    k: usize,
+    settings: &KMeansSettings,
+) -> Vec<(RgbColor, usize)> {
+    let (points, weights) = unique_colors(pixels);
+    let k = k.min(points.len());
+    if k == 0 {
+        return vec![];
+    }
+
+    let mut centers = initial_centers(&points, &weights, k, settings.seed);
+    let mut assignments = vec![0; points.len()];
+
+    for _ in 0..settings.max_iterations {
+        for (point, assignment) in points.iter().zip(assignments.iter_mut()) {
+            *assignment = nearest_center(point, &centers).0;
+        }
+
+        let mut sums = vec![[0.0; 4]; k];
+        for ((point, weight), assignment) in points.iter().zip(&weights).zip(&assignments) {
+            let weight = *weight as f64;
+            let sum = &mut sums[*assignment];
+            sum[0] += point.l * weight;
+            sum[1] += point.a * weight;
+            sum[2] += point.b * weight;
+            sum[3] += weight;
+        }
+
+        let mut max_shift: f64 = 0.0;
+        for (center, [l, a, b, weight]) in centers.iter_mut().zip(sums) {
+            // A cluster without points keeps its center.
+            if weight == 0.0 {
+                continue;
+            }
+
+            let new_center = LabColor::new(l / weight, a / weight, b / weight);
+            max_shift = max_shift.max(distance_squared(center, &new_center).sqrt());
+            *center = new_center;
+        }
+
+        if max_shift < settings.convergence_threshold {
+            break;
+        }
+    }
+
+    let mut populations = vec![0; k];
+    for (point, weight) in points.iter().zip(&weights) {
+        populations[nearest_center(point, &centers).0] += weight;
+    }
+
+    let mut clusters = centers
+        .into_iter()
+        .zip(populations)
+        .filter(|(_, population)| *population > 0)
+        .map(|(center, population)| (center.to_rgb(), population))
+        .collect::<Vec<_>>();
+    // The sort is stable, so clusters of equal population keep their (deterministic) order.
+    clusters.sort_by(|(_, population_a), (_, population_b)| population_b.cmp(population_a));
+
+    clusters
+}
+
+/// Returns every unique color of the given pixels in CIELAB, along with how often it occurs.
+fn unique_colors(pixels: &[RgbColor]) -> (Vec<LabColor>, Vec<usize>) {
+    count_unique_colors(pixels)
+        .into_iter()
+        .map(|([red, green, blue], count)| (RgbColor::new(red, green, blue).to_lab(), count))
+        .unzip()
+}
+
+/// Picks `k` initial centers using k-means++: every next center is picked with a probability
+/// proportional to its weight and its squared distance to the nearest center picked so far.
+fn initial_centers(points: &[LabColor], weights: &[usize], k: usize, seed: u64) -> Vec<LabColor> {
+    let mut rng = SplitMix64::new(seed);
+    let mut centers = Vec::with_capacity(k);
+
+    let total_weight = weights.iter().sum::<usize>() as f64;
+    centers.push(
+        points[pick_weighted(
+            weights.iter().map(|weight| *weight as f64),
+            total_weight,
+            &mut rng,
+        )],
+    );
+
+    let mut distances = points
+        .iter()
+        .map(|point| distance_squared(point, &centers[0]))
+        .collect::<Vec<_>>();
+
+    while centers.len() < k {
+        let scores = distances
+            .iter()
+            .zip(weights)
+            .map(|(distance, weight)| distance * *weight as f64)
+            .collect::<Vec<_>>();
+        let total_score = scores.iter().sum::<f64>();
+
+        // Every remaining point coincides with a center, so there is nothing left to pick.
+        if total_score <= 0.0 {
+            break;
+        }
+
+        let center = points[pick_weighted(scores.iter().copied(), total_score, &mut rng)];
+        for (point, distance) in points.iter().zip(distances.iter_mut()) {
+            *distance = distance.min(distance_squared(point, &center));
+        }
+        centers.push(center);
+    }
+
+    centers
+}
+
+/// Picks the index of a value with a probability proportional to that value.
+fn pick_weighted(values: impl Iterator<Item = f64>, total: f64, rng: &mut SplitMix64) -> usize {
+    let target = rng.next_f64() * total;
+
+    let mut cumulative = 0.0;
+    let mut last_index = 0;
+    for (index, value) in values.enumerate() {
+        if value <= 0.0 {
+            continue;
+        }
+
+        cumulative += value;
+        last_index = index;
+        if cumulative > target {
+            break;
+        }
+    }
+
+    last_index
+}
+
+fn nearest_center(point: &LabColor, centers: &[LabColor]) -> (usize, f64) {
+    centers
+        .iter()
+        .map(|center| distance_squared(point, center))
+        .enumerate()
+        .fold((0, f64::INFINITY), |nearest, (index, distance)| {
+            if distance < nearest.1 {
+                (index, distance)
+            } else {
+                nearest
+            }
+        })
+}
+
+fn distance_squared(a: &LabColor, b: &LabColor) -> f64 {
+    (a.l - b.l).powi(2) + (a.a - b.a).powi(2) + (a.b - b.b).powi(2)
+}
+
+/// A small pseudo-random number generator (SplitMix64), used instead of `rand` so the sequence
+/// for a given seed never changes between versions.
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in the range `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
@@ -0,0 +1,113 @@
+use prisma::Rgb;
+
+use crate::{functions::count_unique_colors, models::colors::RgbColor};
+
+/// Quantizes the given pixels into (at most) `k` colors using median cut, and returns every
+/// color along with the amount of pixels it represents.
+///
+/// Starting with a single box containing every unique color, the box with the widest range in
+/// any color channel is split in two at the median pixel along that channel, until there are
+/// `k` boxes (or no box can be split any further). Every box results in the average color of
+/// its pixels. The colors are ordered by population, from largest to smallest.
+pub fn median_cut_quantize(pixels: &[RgbColor], k: usize) -> Vec<(RgbColor, usize)> {
+    if k == 0 || pixels.is_empty() {
+        return vec![];
+    }
+
+    let mut boxes = vec![ColorBox::new(count_unique_colors(pixels))];
+    while boxes.len() < k {
+        let widest_box = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, color_box)| color_box.colors.len() > 1)
+            .max_by_key(|(index, color_box)| {
+                // Prefer the first box if multiple boxes are equally wide.
+                (color_box.widest_channel().1, std::cmp::Reverse(*index))
+            })
+            .map(|(index, _)| index);
+
+        match widest_box {
+            Some(index) => {
+                let (first, second) = boxes.remove(index).split();
+                boxes.insert(index, second);
+                boxes.insert(index, first);
+            }
+            None => break,
+        }
+    }
+
+    let mut colors = boxes
+        .iter()
+        .map(|color_box| (color_box.average(), color_box.population()))
+        .collect::<Vec<_>>();
+    colors.sort_by(|(_, population_a), (_, population_b)| population_b.cmp(population_a));
+
+    colors
+}
+
+/// A box in the RGB color cube, containing unique colors and how often they occur.
+struct ColorBox {
+    colors: Vec<([u8; 3], usize)>,
+}
+
+impl ColorBox {
+    fn new(colors: Vec<([u8; 3], usize)>) -> Self {
+        Self { colors }
+    }
+
+    fn population(&self) -> usize {
+        self.colors.iter().map(|(_, count)| count).sum()
+    }
+
+    /// Returns the channel with the widest range of values, and that range.
+    fn widest_channel(&self) -> (usize, u8) {
+        (0..3)
+            .map(|channel| {
+                let values = self.colors.iter().map(|(rgb, _)| rgb[channel]);
+                let range = values.clone().max().unwrap_or(0) - values.min().unwrap_or(0);
+                (channel, range)
+            })
+            .fold((0, 0), |widest, channel| {
+                if channel.1 > widest.1 {
+                    channel
+                } else {
+                    widest
+                }
+            })
+    }
+
+    /// Splits this box at the median pixel along its widest channel. Both boxes contain at
+    /// least one color.
+    fn split(mut self) -> (ColorBox, ColorBox) {
+        let (channel, _) = self.widest_channel();
+        self.colors.sort_by_key(|(rgb, _)| (rgb[channel], *rgb));
+
+        let half_population = self.population() / 2;
+        let mut cumulative = 0;
+        let mut split_index = self.colors.len() - 1;
+        for (index, (_, count)) in self.colors.iter().enumerate() {
+            cumulative += count;
+            if cumulative > half_population {
+                split_index = index;
+                break;
+            }
+        }
+        let split_index = split_index.clamp(1, self.colors.len() - 1);
+
+        let second = self.colors.split_off(split_index);
+        (self, ColorBox::new(second))
+    }
+
+    fn average(&self) -> RgbColor {
+        let population = self.population() as u64;
+        let mut sums = [0u64; 3];
+        for (rgb, count) in &self.colors {
+            for (sum, value) in sums.iter_mut().zip(rgb) {
+                *sum += *value as u64 * *count as u64;
+            }
+        }
+
+        let [red, green, blue] = sums.map(|sum| (sum / population) as u8);
+        Rgb::new(red, green, blue)
+    }
+}
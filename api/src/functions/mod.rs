@@ -1,11 +1,53 @@
+mod adaptive_interval;
+mod assign_screen_targets;
 mod calculate_dominant_colors;
+mod calculate_edge_zone_colors;
+mod calculate_led_colors;
 mod capture_pixels;
 mod color_conversion;
+mod color_difference;
+mod color_harmony;
+mod color_smoother;
+mod decide_submission;
+mod deduplicate_colors;
+mod detect_letterbox;
+mod downsample_frame;
+mod frame_fingerprint;
 mod get_colors;
+mod grade_color;
+mod hue_histogram;
+mod interpolate_gradient;
+mod kmeans_lab;
+mod median_cut_quantize;
+mod octree_quantize;
+mod palette_extractor;
+mod perceptual_color_conversion;
+mod pixel_source;
 mod print_title;
 
+pub use adaptive_interval::*;
+pub use assign_screen_targets::*;
 pub use calculate_dominant_colors::*;
+pub use calculate_edge_zone_colors::*;
+pub use calculate_led_colors::*;
 pub use capture_pixels::*;
 pub use color_conversion::*;
+pub use color_difference::*;
+pub use color_harmony::*;
+pub use color_smoother::*;
+pub use decide_submission::*;
+pub use deduplicate_colors::*;
+pub use detect_letterbox::*;
+pub use downsample_frame::*;
+pub use frame_fingerprint::*;
 pub use get_colors::*;
+pub use grade_color::*;
+pub use hue_histogram::*;
+pub use interpolate_gradient::*;
+pub use kmeans_lab::*;
+pub use median_cut_quantize::*;
+pub use octree_quantize::*;
+pub use palette_extractor::*;
+pub use perceptual_color_conversion::*;
+pub use pixel_source::*;
 pub use print_title::*;
@@ -0,0 +1,146 @@
+use prisma::Rgb;
+
+use crate::{functions::count_unique_colors, models::colors::RgbColor};
+
+/// The depth of the octree, which is one level per bit of a color channel.
+const MAX_DEPTH: usize = 8;
+
+/// Quantizes the given pixels into (at most) `k` colors using an octree, and returns every color
+/// along with the amount of pixels it represents.
+///
+/// Every unique color is inserted into a tree that splits the RGB color cube into eight smaller
+/// cubes per level. The tree is then reduced from the deepest level up, merging the least
+/// populated nodes first, until at most `k` leaves are left. Nodes are never merged if that
+/// would leave fewer than `k` leaves, so if more than `k` leaves remain, only the `k` most
+/// populated are used. The colors are ordered by population, from largest to smallest.
+pub fn octree_quantize(pixels: &[RgbColor], k: usize) -> Vec<(RgbColor, usize)> {
+    if k == 0 || pixels.is_empty() {
+        return vec![];
+    }
+
+    let mut tree = Octree::new();
+    for (rgb, count) in count_unique_colors(pixels) {
+        tree.insert(rgb, count);
+    }
+    tree.reduce(k);
+
+    let mut colors = tree
+        .nodes
+        .iter()
+        .filter(|node| node.is_leaf)
+        .map(|node| {
+            let [red, green, blue] = node.sums.map(|sum| (sum / node.population as u64) as u8);
+            (Rgb::new(red, green, blue), node.population)
+        })
+        .collect::<Vec<_>>();
+    colors.sort_by(|(_, population_a), (_, population_b)| population_b.cmp(population_a));
+    colors.truncate(k);
+
+    colors
+}
+
+#[derive(Debug, Clone, Default)]
+struct OctreeNode {
+    /// The indices of the child nodes, where 0 (the root) means there is no child.
+    children: [usize; 8],
+    is_leaf: bool,
+    /// The amount of pixels in this node and all of its descendants.
+    population: usize,
+    /// The sum of every color channel of all pixels in this node and all of its descendants.
+    sums: [u64; 3],
+}
+
+struct Octree {
+    nodes: Vec<OctreeNode>,
+    /// The indices of the nodes with children, per level.
+    reducible: Vec<Vec<usize>>,
+    leaf_count: usize,
+}
+
+impl Octree {
+    fn new() -> Self {
+        Self {
+            nodes: vec![OctreeNode::default()],
+            reducible: vec![vec![]; MAX_DEPTH],
+            leaf_count: 0,
+        }
+    }
+
+    fn insert(&mut self, rgb: [u8; 3], count: usize) {
+        let mut index = 0;
+        for level in 0..=MAX_DEPTH {
+            let node = &mut self.nodes[index];
+            node.population += count;
+            for (sum, value) in node.sums.iter_mut().zip(rgb) {
+                *sum += value as u64 * count as u64;
+            }
+
+            if level == MAX_DEPTH {
+                if !node.is_leaf {
+                    node.is_leaf = true;
+                    self.leaf_count += 1;
+                }
+                return;
+            }
+
+            let shift = 7 - level;
+            let child = (((rgb[0] >> shift) & 1) << 2
+                | ((rgb[1] >> shift) & 1) << 1
+                | ((rgb[2] >> shift) & 1)) as usize;
+
+            if self.nodes[index].children == [0; 8] {
+                self.reducible[level].push(index);
+            }
+            if self.nodes[index].children[child] == 0 {
+                self.nodes.push(OctreeNode::default());
+                let child_index = self.nodes.len() - 1;
+                self.nodes[index].children[child] = child_index;
+            }
+            index = self.nodes[index].children[child];
+        }
+    }
+
+    /// Merges nodes into leaves until at most `max_leaves` leaves are left, without ever merging
+    /// a node that would leave fewer leaves.
+    fn reduce(&mut self, max_leaves: usize) {
+        for level in (0..MAX_DEPTH).rev() {
+            let mut candidates = std::mem::take(&mut self.reducible[level]);
+            // The sort is stable, so nodes of equal population are merged in insertion order.
+            candidates.sort_by_key(|index| self.nodes[*index].population);
+
+            // Nodes above a skipped node can't be merged, as they would merge more than their
+            // children, so the reduction ends after this level.
+            let mut skipped_any = false;
+            for index in candidates {
+                if self.leaf_count <= max_leaves {
+                    return;
+                }
+
+                let child_count = self.nodes[index]
+                    .children
+                    .iter()
+                    .filter(|child| **child != 0)
+                    .count();
+                if self.leaf_count + 1 - child_count < max_leaves {
+                    skipped_any = true;
+                    continue;
+                }
+
+                // The children are all leaves, since every deeper level has been reduced already.
+                // Their pixels are already part of this node's population and sums.
+                let children = std::mem::take(&mut self.nodes[index].children);
+                for child in children.into_iter().filter(|child| *child != 0) {
+                    self.nodes[child].is_leaf = false;
+                    self.leaf_count -= 1;
+                }
+
+                self.nodes[index].is_leaf = true;
+                self.leaf_count += 1;
+            }
+
+            if skipped_any {
+                return;
+            }
+        }
+    }
+}
@@ -0,0 +1,325 @@
+use anyhow::*;
+use prisma::Rgb;
+
+use crate::{
+    config::{DominantColorAlgorithm, HueHistogramSettings, KMeansSettings},
+    functions::*,
+    models::colors::{DominantColor, RgbColor},
+};
+
+/// An algorithm that extracts a palette of dominant colors from the pixels of a frame.
+///
+/// Every implementation is expected to:
+/// - return at most `sample_size` colors, and at least one color for non-empty input;
+/// - order the colors from most to least dominant;
+/// - give every color a weight greater than 0 and at most 1, with all weights adding up to at
+///   most 1. The weight is the color's relative prominence, which is usually (but not
+///   necessarily) the share of the pixels it represents;
+/// - return no colors for empty input or a sample size of 0;
+/// - return a single color (close to the input color) with a weight of 1 for input consisting
+///   of a single color.
+///
+/// Use [`check_palette_extractor`] to verify an implementation against these requirements.
+pub trait PaletteExtractor: Send + Sync {
+    /// The name the extractor is registered under.
+    fn name(&self) -> &str;
+
+    /// Extracts (at most) `sample_size` dominant colors from the given pixels.
+    fn extract(&self, pixels: &[RgbColor], sample_size: u8) -> Vec<DominantColor>;
+}
+
+/// A collection of palette extractors, looked up by name.
+#[derive(Default)]
+pub struct PaletteExtractorRegistry {
+    extractors: Vec<Box<dyn PaletteExtractor>>,
+}
+
+impl PaletteExtractorRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry containing an extractor for every `DominantColorAlgorithm`, using the
+    /// given settings for the algorithms that have them.
+    pub fn with_builtin_extractors(
+        kmeans: &KMeansSettings,
+        hue_histogram: &HueHistogramSettings,
+    ) -> Self {
+        let mut registry = Self::new();
+        registry.register(ColorThiefExtractor);
+        registry.register(PigmntsExtractor);
+        registry.register(KMeansLabExtractor::new(kmeans.clone()));
+        registry.register(OctreeExtractor);
+        registry.register(MedianCutExtractor);
+        registry.register(HueHistogramExtractor::new(hue_histogram.clone()));
+
+        registry
+    }
+
+    /// Adds the given extractor to this registry, replacing any extractor with the same name.
+    pub fn register(&mut self, extractor: impl PaletteExtractor + 'static) {
+        self.extractors
+            .retain(|registered| registered.name() != extractor.name());
+        self.extractors.push(Box::new(extractor));
+    }
+
+    /// Returns the extractor registered under the given name, if any.
+    pub fn get(&self, name: &str) -> Option<&dyn PaletteExtractor> {
+        self.extractors
+            .iter()
+            .find(|extractor| extractor.name() == name)
+            .map(|extractor| extractor.as_ref())
+    }
+
+    /// Returns the names of all registered extractors, in the order they were registered.
+    pub fn names(&self) -> Vec<&str> {
+        self.extractors
+            .iter()
+            .map(|extractor| extractor.name())
+            .collect()
+    }
+
+    /// Extracts the dominant colors of the given pixels using the extractor registered under the
+    /// given name.
+    pub fn extract(
+        &self,
+        name: &str,
+        pixels: &[RgbColor],
+        sample_size: u8,
+    ) -> Result<Vec<DominantColor>> {
+        let extractor = self.get(name).ok_or_else(|| {
+            anyhow!(
+                "No palette extractor named \"{}\", expected one of: {}",
+                name,
+                self.names().join(", ")
+            )
+        })?;
+
+        Ok(extractor.extract(pixels, sample_size))
+    }
+}
+
+/// The maximum difference per color channel between a single-color input and the color
+/// extracted from it. Some algorithms quantize colors before clustering them.
+const SINGLE_COLOR_TOLERANCE: i32 = 8;
+
+/// The maximum rounding error of weights, which some algorithms calculate with single precision.
+const WEIGHT_TOLERANCE: f64 = 1e-4;
+
+/// Checks whether the given extractor meets the requirements of `PaletteExtractor`, and returns
+/// a list of the requirements it violates.
+pub fn check_palette_extractor(extractor: &dyn PaletteExtractor) -> Vec<String> {
+    let mut errors = Vec::new();
+    let name = extractor.name();
+
+    if !extractor.extract(&[], 3).is_empty() {
+        errors.push(format!("{}: returned colors for empty input", name));
+    }
+
+    let pixels = conformance_pixels();
+    if !extractor.extract(&pixels, 0).is_empty() {
+        errors.push(format!("{}: returned colors for a sample size of 0", name));
+    }
+
+    for sample_size in 1..=10 {
+        let colors = extractor.extract(&pixels, sample_size);
+        let weights = colors
+            .iter()
+            .map(|dominant_color| dominant_color.weight)
+            .collect::<Vec<_>>();
+
+        if colors.is_empty() || colors.len() > sample_size as usize {
+            errors.push(format!(
+                "{}: returned {} colors for a sample size of {}",
+                name,
+                colors.len(),
+                sample_size
+            ));
+        }
+        if weights
+            .iter()
+            .any(|weight| !(*weight > 0.0 && *weight <= 1.0 + WEIGHT_TOLERANCE))
+        {
+            errors.push(format!(
+                "{}: returned weights outside of (0, 1] for a sample size of {}: {:?}",
+                name, sample_size, weights
+            ));
+        }
+        if weights.iter().sum::<f64>() > 1.0 + WEIGHT_TOLERANCE {
+            errors.push(format!(
+                "{}: returned weights adding up to more than 1 for a sample size of {}: {:?}",
+                name, sample_size, weights
+            ));
+        }
+        if weights.windows(2).any(|pair| pair[0] < pair[1]) {
+            errors.push(format!(
+                "{}: returned colors that aren't ordered by weight for a sample size of {}: {:?}",
+                name, sample_size, weights
+            ));
+        }
+    }
+
+    for color in [
+        Rgb::new(0, 0, 0),
+        Rgb::new(255, 255, 255),
+        Rgb::new(200, 30, 90),
+    ] {
+        for pixel_count in [1, 500] {
+            for sample_size in [1, 5] {
+                let colors = extractor.extract(&vec![color; pixel_count], sample_size);
+                let is_close = |extracted: &RgbColor| {
+                    [
+                        (extracted.red(), color.red()),
+                        (extracted.green(), color.green()),
+                        (extracted.blue(), color.blue()),
+                    ]
+                    .iter()
+                    .all(|(a, b)| (*a as i32 - *b as i32).abs() <= SINGLE_COLOR_TOLERANCE)
+                };
+
+                let is_valid = match colors.as_slice() {
+                    [dominant_color] => {
+                        is_close(&dominant_color.color)
+                            && (dominant_color.weight - 1.0).abs() <= WEIGHT_TOLERANCE
+                    }
+                    _ => false,
+                };
+                if !is_valid {
+                    errors.push(format!(
+                        "{}: returned {:?} for {} pixels of {:?} and a sample size of {}",
+                        name, colors, pixel_count, color, sample_size
+                    ));
+                }
+            }
+        }
+    }
+
+    errors
+}
+
+/// A small, fixed image with areas of various sizes, colors and gradients.
+fn conformance_pixels() -> Vec<RgbColor> {
+    let areas = [
+        (Rgb::new(20, 20, 30), 900),
+        (Rgb::new(230, 40, 40), 400),
+        (Rgb::new(40, 200, 60), 250),
+        (Rgb::new(30, 60, 220), 150),
+        (Rgb::new(240, 240, 240), 100),
+        (Rgb::new(250, 200, 20), 50),
+    ];
+
+    let mut pixels = areas
+        .iter()
+        .flat_map(|(color, count)| std::iter::repeat_n(*color, *count))
+        .collect::<Vec<_>>();
+    pixels.extend((0..=255u8).map(|value| Rgb::new(value, 255 - value, value / 2)));
+
+    pixels
+}
+
+/// The extractor of the `color_thief` algorithm.
+pub struct ColorThiefExtractor;
+
+impl PaletteExtractor for ColorThiefExtractor {
+    fn name(&self) -> &str {
+        DominantColorAlgorithm::ColorThief.name()
+    }
+
+    fn extract(&self, pixels: &[RgbColor], sample_size: u8) -> Vec<DominantColor> {
+        color_thief_alg(pixels, sample_size)
+    }
+}
+
+/// The extractor of the `pigmnts` algorithm.
+pub struct PigmntsExtractor;
+
+impl PaletteExtractor for PigmntsExtractor {
+    fn name(&self) -> &str {
+        DominantColorAlgorithm::Pigmnts.name()
+    }
+
+    fn extract(&self, pixels: &[RgbColor], sample_size: u8) -> Vec<DominantColor> {
+        pigmnts_alg(pixels, sample_size)
+    }
+}
+
+/// The extractor of the `kmeans_lab` algorithm.
+pub struct KMeansLabExtractor {
+    settings: KMeansSettings,
+}
+
+impl KMeansLabExtractor {
+    pub fn new(settings: KMeansSettings) -> Self {
+        Self { settings }
+    }
+}
+
+impl PaletteExtractor for KMeansLabExtractor {
+    fn name(&self) -> &str {
+        DominantColorAlgorithm::KMeansLab.name()
+    }
+
+    fn extract(&self, pixels: &[RgbColor], sample_size: u8) -> Vec<DominantColor> {
+        with_weights(
+            kmeans_lab(pixels, sample_size as usize, &self.settings),
+            pixels.len(),
+        )
+    }
+}
+
+/// The extractor of the `octree` algorithm.
+pub struct OctreeExtractor;
+
+impl PaletteExtractor for OctreeExtractor {
+    fn name(&self) -> &str {
+        DominantColorAlgorithm::Octree.name()
+    }
+
+    fn extract(&self, pixels: &[RgbColor], sample_size: u8) -> Vec<DominantColor> {
+        with_weights(octree_quantize(pixels, sample_size as usize), pixels.len())
+    }
+}
+
+/// The extractor of the `median_cut` algorithm.
+pub struct MedianCutExtractor;
+
+impl PaletteExtractor for MedianCutExtractor {
+    fn name(&self) -> &str {
+        DominantColorAlgorithm::MedianCut.name()
+    }
+
+    fn extract(&self, pixels: &[RgbColor], sample_size: u8) -> Vec<DominantColor> {
+        with_weights(
+            median_cut_quantize(pixels, sample_size as usize),
+            pixels.len(),
+        )
+    }
+}
+
+/// The extractor of the `hue_histogram` algorithm.
+///
+/// The weight of every color is its hue bin's share of the total vividness, not its share of the
+/// pixels.
+pub struct HueHistogramExtractor {
+    settings: HueHistogramSettings,
+}
+
+impl HueHistogramExtractor {
+    pub fn new(settings: HueHistogramSettings) -> Self {
+        Self { settings }
+    }
+}
+
+impl PaletteExtractor for HueHistogramExtractor {
+    fn name(&self) -> &str {
+        DominantColorAlgorithm::HueHistogram.name()
+    }
+
+    fn extract(&self, pixels: &[RgbColor], sample_size: u8) -> Vec<DominantColor> {
+        hue_histogram(pixels, sample_size as usize, &self.settings)
+            .into_iter()
+            .map(|(color, weight)| DominantColor::new(color, weight))
+            .collect()
+    }
+}
@@ -0,0 +1,403 @@
+use prisma::Rgb;
+
+use crate::{
+    functions::{u8_to_f64, ToRgb},
+    models::colors::{
+        HomeAssistantRgbbColor, HsvColor, LabColor, OklabColor, OklchColor, RgbColor, XyColor,
+        XyzColor,
+    },
+};
+
+/// The CIE XYZ coordinates of the D65 white point, the white of sRGB.
+const D65_WHITE: XyzColor = XyzColor {
+    x: 0.95047,
+    y: 1.0,
+    z: 1.08883,
+};
+
+/// The CIE xy chromaticity of the D65 white point, used for black, which has no chromaticity.
+const D65_WHITE_CHROMATICITY: (f64, f64) = (0.3127, 0.3290);
+
+/// The CIELAB constants ε and κ, as exact fractions, as recommended by the CIE.
+const LAB_EPSILON: f64 = 216.0 / 24389.0;
+const LAB_KAPPA: f64 = 24389.0 / 27.0;
+
+/// The amount of times the chroma range is halved when bringing an OKLCH color into the sRGB
+/// gamut, which is far more precise than an 8-bit channel.
+const GAMUT_MAPPING_ITERATIONS: usize = 24;
+
+/// Allows channels to be slightly out of range, as colors in the gamut don't always convert
+/// exactly.
+const GAMUT_TOLERANCE: f64 = 1e-6;
+
+pub trait ToXyz {
+    fn to_xyz(&self) -> XyzColor;
+}
+
+pub trait ToXy {
+    fn to_xy(&self) -> XyColor;
+}
+
+pub trait ToLab {
+    fn to_lab(&self) -> LabColor;
+}
+
+pub trait ToOklab {
+    fn to_oklab(&self) -> OklabColor;
+}
+
+pub trait ToOklch {
+    fn to_oklch(&self) -> OklchColor;
+}
+
+/// Implemented by the colors that are stored in sRGB or derived from it, like HSV. These are
+/// converted to the perceptual color spaces through 8-bit sRGB, while the perceptual colors
+/// convert between each other without rounding.
+pub trait SrgbBasedColor: ToRgb {}
+
+impl SrgbBasedColor for RgbColor {}
+
+impl SrgbBasedColor for Rgb<f64> {}
+
+impl SrgbBasedColor for HsvColor {}
+
+impl SrgbBasedColor for HomeAssistantRgbbColor {}
+
+/// Converts to CIE XYZ using the sRGB primaries and D65 white point, after removing the sRGB
+/// gamma.
+impl<T> ToXyz for T
+where
+    T: SrgbBasedColor,
+{
+    fn to_xyz(&self) -> XyzColor {
+        linear_srgb_to_xyz(to_linear_srgb(&self.to_rgb()))
+    }
+}
+
+impl ToXyz for XyzColor {
+    fn to_xyz(&self) -> XyzColor {
+        *self
+    }
+}
+
+impl ToXyz for XyColor {
+    fn to_xyz(&self) -> XyzColor {
+        (*self).into()
+    }
+}
+
+impl ToXyz for LabColor {
+    fn to_xyz(&self) -> XyzColor {
+        (*self).into()
+    }
+}
+
+impl ToXyz for OklabColor {
+    fn to_xyz(&self) -> XyzColor {
+        (*self).into()
+    }
+}
+
+impl ToXyz for OklchColor {
+    fn to_xyz(&self) -> XyzColor {
+        OklabColor::from(*self).into()
+    }
+}
+
+impl<T> ToXy for T
+where
+    T: ToXyz,
+{
+    fn to_xy(&self) -> XyColor {
+        self.to_xyz().into()
+    }
+}
+
+/// Converts to CIELAB relative to the D65 white point.
+impl<T> ToLab for T
+where
+    T: ToXyz,
+{
+    fn to_lab(&self) -> LabColor {
+        self.to_xyz().into()
+    }
+}
+
+/// Converts to OKLab using the matrices from "A perceptual color space for image processing" by
+/// Björn Ottosson.
+impl<T> ToOklab for T
+where
+    T: SrgbBasedColor,
+{
+    fn to_oklab(&self) -> OklabColor {
+        linear_srgb_to_oklab(to_linear_srgb(&self.to_rgb()))
+    }
+}
+
+impl ToOklab for XyzColor {
+    fn to_oklab(&self) -> OklabColor {
+        (*self).into()
+    }
+}
+
+impl ToOklab for XyColor {
+    fn to_oklab(&self) -> OklabColor {
+        XyzColor::from(*self).into()
+    }
+}
+
+impl ToOklab for LabColor {
+    fn to_oklab(&self) -> OklabColor {
+        XyzColor::from(*self).into()
+    }
+}
+
+impl ToOklab for OklabColor {
+    fn to_oklab(&self) -> OklabColor {
+        *self
+    }
+}
+
+impl ToOklab for OklchColor {
+    fn to_oklab(&self) -> OklabColor {
+        (*self).into()
+    }
+}
+
+impl<T> ToOklch for T
+where
+    T: ToOklab,
+{
+    fn to_oklch(&self) -> OklchColor {
+        self.to_oklab().into()
+    }
+}
+
+impl ToRgb for XyzColor {
+    fn to_rgb(&self) -> RgbColor {
+        from_linear_srgb(xyz_to_linear_srgb(self))
+    }
+}
+
+impl ToRgb for XyColor {
+    fn to_rgb(&self) -> RgbColor {
+        XyzColor::from(*self).to_rgb()
+    }
+}
+
+impl ToRgb for LabColor {
+    fn to_rgb(&self) -> RgbColor {
+        XyzColor::from(*self).to_rgb()
+    }
+}
+
+impl ToRgb for OklabColor {
+    fn to_rgb(&self) -> RgbColor {
+        from_linear_srgb(oklab_to_linear_srgb(self))
+    }
+}
+
+/// Colors outside of the sRGB gamut are brought into it by lowering their chroma, which keeps
+/// their lightness and hue (unlike clipping every channel on its own).
+impl ToRgb for OklchColor {
+    fn to_rgb(&self) -> RgbColor {
+        let with_chroma = |c: f64| OklabColor::from(OklchColor::new(self.l, c, self.h));
+        if is_in_srgb_gamut(&with_chroma(self.c)) {
+            return with_chroma(self.c).to_rgb();
+        }
+
+        let (mut in_gamut, mut out_of_gamut) = (0.0, self.c);
+        for _ in 0..GAMUT_MAPPING_ITERATIONS {
+            let c = (in_gamut + out_of_gamut) / 2.0;
+            if is_in_srgb_gamut(&with_chroma(c)) {
+                in_gamut = c;
+            } else {
+                out_of_gamut = c;
+            }
+        }
+
+        with_chroma(in_gamut).to_rgb()
+    }
+}
+
+impl From<XyzColor> for XyColor {
+    fn from(xyz: XyzColor) -> Self {
+        let sum = xyz.x + xyz.y + xyz.z;
+        if sum <= 0.0 {
+            let (x, y) = D65_WHITE_CHROMATICITY;
+            return Self::new(x, y, 0.0);
+        }
+
+        Self::new(xyz.x / sum, xyz.y / sum, xyz.y)
+    }
+}
+
+impl From<XyColor> for XyzColor {
+    fn from(xy: XyColor) -> Self {
+        if xy.y <= 0.0 {
+            return Self::new(0.0, 0.0, 0.0);
+        }
+
+        Self::new(
+            xy.x * xy.luminance / xy.y,
+            xy.luminance,
+            (1.0 - xy.x - xy.y) * xy.luminance / xy.y,
+        )
+    }
+}
+
+impl From<XyzColor> for LabColor {
+    fn from(xyz: XyzColor) -> Self {
+        let f = |t: f64| {
+            if t > LAB_EPSILON {
+                t.cbrt()
+            } else {
+                (LAB_KAPPA * t + 16.0) / 116.0
+            }
+        };
+
+        let fx = f(xyz.x / D65_WHITE.x);
+        let fy = f(xyz.y / D65_WHITE.y);
+        let fz = f(xyz.z / D65_WHITE.z);
+
+        Self::new(116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
+    }
+}
+
+impl From<LabColor> for XyzColor {
+    fn from(lab: LabColor) -> Self {
+        let fy = (lab.l + 16.0) / 116.0;
+        let fx = fy + lab.a / 500.0;
+        let fz = fy - lab.b / 200.0;
+
+        let f_inverse = |f: f64| {
+            if f.powi(3) > LAB_EPSILON {
+                f.powi(3)
+            } else {
+                (116.0 * f - 16.0) / LAB_KAPPA
+            }
+        };
+        let y = if lab.l > LAB_KAPPA * LAB_EPSILON {
+            fy.powi(3)
+        } else {
+            lab.l / LAB_KAPPA
+        };
+
+        Self::new(
+            f_inverse(fx) * D65_WHITE.x,
+            y * D65_WHITE.y,
+            f_inverse(fz) * D65_WHITE.z,
+        )
+    }
+}
+
+/// Converts through linear sRGB, without clipping colors outside of the sRGB gamut.
+impl From<XyzColor> for OklabColor {
+    fn from(xyz: XyzColor) -> Self {
+        linear_srgb_to_oklab(xyz_to_linear_srgb(&xyz))
+    }
+}
+
+/// Converts through linear sRGB, without clipping colors outside of the sRGB gamut.
+impl From<OklabColor> for XyzColor {
+    fn from(oklab: OklabColor) -> Self {
+        linear_srgb_to_xyz(oklab_to_linear_srgb(&oklab))
+    }
+}
+
+impl From<OklabColor> for OklchColor {
+    fn from(oklab: OklabColor) -> Self {
+        let h = oklab.b.atan2(oklab.a).to_degrees();
+
+        Self::new(oklab.l, oklab.a.hypot(oklab.b), h.rem_euclid(360.0))
+    }
+}
+
+impl From<OklchColor> for OklabColor {
+    fn from(oklch: OklchColor) -> Self {
+        let h = oklch.h.to_radians();
+
+        Self::new(oklch.l, oklch.c * h.cos(), oklch.c * h.sin())
+    }
+}
+
+/// Removes the sRGB gamma from the given component (from 0 to 1).
+pub fn srgb_to_linear(component: f64) -> f64 {
+    if component <= 0.04045 {
+        component / 12.92
+    } else {
+        ((component + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Applies the sRGB gamma to the given linear component (from 0 to 1).
+pub fn linear_to_srgb(component: f64) -> f64 {
+    let component = component.clamp(0.0, 1.0);
+    if component <= 0.0031308 {
+        component * 12.92
+    } else {
+        1.055 * component.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+fn to_linear_srgb(rgb: &RgbColor) -> [f64; 3] {
+    [rgb.red(), rgb.green(), rgb.blue()].map(|channel| srgb_to_linear(u8_to_f64(channel)))
+}
+
+/// Applies the sRGB gamma to the given linear channels, clipping channels outside of the 0 to 1
+/// range, and rounds them to the nearest 8-bit value.
+fn from_linear_srgb([r, g, b]: [f64; 3]) -> RgbColor {
+    let to_u8 = |linear: f64| (linear_to_srgb(linear) * u8::MAX as f64).round() as u8;
+
+    Rgb::new(to_u8(r), to_u8(g), to_u8(b))
+}
+
+fn is_in_srgb_gamut(oklab: &OklabColor) -> bool {
+    oklab_to_linear_srgb(oklab)
+        .iter()
+        .all(|channel| (-GAMUT_TOLERANCE..=1.0 + GAMUT_TOLERANCE).contains(channel))
+}
+
+fn linear_srgb_to_xyz([r, g, b]: [f64; 3]) -> XyzColor {
+    XyzColor::new(
+        0.4124564 * r + 0.3575761 * g + 0.1804375 * b,
+        0.2126729 * r + 0.7151522 * g + 0.0721750 * b,
+        0.0193339 * r + 0.1191920 * g + 0.9503041 * b,
+    )
+}
+
+fn xyz_to_linear_srgb(xyz: &XyzColor) -> [f64; 3] {
+    [
+        3.2404542 * xyz.x - 1.5371385 * xyz.y - 0.4985314 * xyz.z,
+        -0.9692660 * xyz.x + 1.8760108 * xyz.y + 0.0415560 * xyz.z,
+        0.0556434 * xyz.x - 0.2040259 * xyz.y + 1.0572252 * xyz.z,
+    ]
+}
+
+fn linear_srgb_to_oklab([r, g, b]: [f64; 3]) -> OklabColor {
+    let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+
+    OklabColor::new(
+        0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+        1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+        0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+    )
+}
+
+/// Converts the given OKLab color to linear sRGB channels, which are outside of the 0 to 1
+/// range for colors outside of the sRGB gamut.
+fn oklab_to_linear_srgb(oklab: &OklabColor) -> [f64; 3] {
+    let l_ = oklab.l + 0.3963377774 * oklab.a + 0.2158037573 * oklab.b;
+    let m_ = oklab.l - 0.1055613458 * oklab.a - 0.0638541728 * oklab.b;
+    let s_ = oklab.l - 0.0894841775 * oklab.a - 1.2914855480 * oklab.b;
+
+    let (l, m, s) = (l_.powi(3), m_.powi(3), s_.powi(3));
+
+    [
+        4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s,
+        -1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s,
+        -0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s,
+    ]
+}
@@ -0,0 +1,268 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::*;
+use screenshots::Screen;
+
+use crate::{
+    functions::{downsample_frame::box_downsample, *},
+    models::{colors::RgbColor, frame::Frame},
+};
+
+/// A source of frames to calculate colors from.
+pub trait PixelSource {
+    /// A short, human-readable description of the source, used for logging.
+    fn description(&self) -> String;
+
+    /// Captures the next frame from this source.
+    fn capture(&mut self) -> Result<Frame>;
+
+    /// Captures the next frame from this source, downsampled by the factor `downsample_factor`
+    /// returns for the size of the full frame, where the source supports it. The frame's `scale`
+    /// is the factor it was actually downsampled by.
+    ///
+    /// By default, this captures the full frame.
+    fn capture_downsampled(
+        &mut self,
+        downsample_factor: &dyn Fn(u32, u32) -> u32,
+    ) -> Result<Frame> {
+        let _ = downsample_factor;
+        self.capture()
+    }
+
+    /// The size of the full frames this source captures, if it is known before capturing.
+    ///
+    /// By default, the size is unknown.
+    fn size(&self) -> Option<(u32, u32)> {
+        None
+    }
+}
+
+/// A pixel source that captures the contents of a screen.
+pub struct ScreenPixelSource {
+    screen: Screen,
+    capturer: ScreenCapturer,
+}
+
+impl ScreenPixelSource {
+    pub fn new(screen: Screen) -> Self {
+        Self {
+            screen,
+            capturer: ScreenCapturer::new(),
+        }
+    }
+}
+
+impl PixelSource for ScreenPixelSource {
+    fn description(&self) -> String {
+        format!(
+            "screen {} ({}x{})",
+            self.screen.display_info.id,
+            self.screen.display_info.width,
+            self.screen.display_info.height
+        )
+    }
+
+    fn capture(&mut self) -> Result<Frame> {
+        self.capturer.capture(&self.screen, 1)
+    }
+
+    fn capture_downsampled(
+        &mut self,
+        downsample_factor: &dyn Fn(u32, u32) -> u32,
+    ) -> Result<Frame> {
+        let (width, height) = self.size().unwrap_or_default();
+
+        self.capturer
+            .capture(&self.screen, downsample_factor(width, height))
+    }
+
+    fn size(&self) -> Option<(u32, u32)> {
+        let display_info = &self.screen.display_info;
+
+        Some((
+            (display_info.width as f32 * display_info.scale_factor) as u32,
+            (display_info.height as f32 * display_info.scale_factor) as u32,
+        ))
+    }
+}
+
+/// A pixel source that reads PNG images from disk.
+///
+/// If the path points to a directory, every PNG image in it is returned in turn (sorted by
+/// file name), starting over after the last one.
+pub struct ImagePixelSource {
+    paths: Vec<PathBuf>,
+    next_index: usize,
+}
+
+impl ImagePixelSource {
+    pub fn new(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Err(anyhow!("Image path {} does not exist", path.display()));
+        }
+
+        let paths = if path.is_dir() {
+            let mut paths = std::fs::read_dir(path)
+                .context(format!("Failed to read image directory {}", path.display()))?
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    path.is_file()
+                        && path
+                            .extension()
+                            .map(|extension| extension.eq_ignore_ascii_case("png"))
+                            .unwrap_or(false)
+                })
+                .collect::<Vec<_>>();
+            paths.sort();
+            paths
+        } else {
+            vec![path.to_path_buf()]
+        };
+
+        if paths.is_empty() {
+            return Err(anyhow!(
+                "No PNG images found in directory {}",
+                path.display()
+            ));
+        }
+
+        Ok(Self {
+            paths,
+            next_index: 0,
+        })
+    }
+}
+
+impl PixelSource for ImagePixelSource {
+    fn description(&self) -> String {
+        match self.paths.len() {
+            1 => format!("image {}", self.paths[0].display()),
+            count => format!("{} images", count),
+        }
+    }
+
+    fn capture(&mut self) -> Result<Frame> {
+        let path = &self.paths[self.next_index];
+        self.next_index = (self.next_index + 1) % self.paths.len();
+
+        let buffer =
+            std::fs::read(path).context(format!("Failed to read image {}", path.display()))?;
+
+        decode_png_pixels(&buffer).context(format!("Failed to decode image {}", path.display()))
+    }
+}
+
+/// A pixel source that combines the frames of several sources into a single frame.
+///
+/// The frames are placed side by side, from left to right, aligned to the top. The space below
+/// frames that are shorter than the tallest frame is excluded, so every pixel of every source
+/// weighs equally when calculating colors.
+///
+/// When downsampling while capturing, every source is downsampled by the same factor, chosen for
+/// the size of the combined frame. Frames that a source could not downsample itself are
+/// downsampled after capturing them.
+pub struct CombinedPixelSource {
+    sources: Vec<Box<dyn PixelSource>>,
+}
+
+impl CombinedPixelSource {
+    pub fn new(sources: Vec<Box<dyn PixelSource>>) -> Self {
+        Self { sources }
+    }
+
+    fn capture_each(
+        &mut self,
+        capture: impl Fn(&mut dyn PixelSource) -> Result<Frame>,
+    ) -> Result<Vec<Frame>> {
+        self.sources
+            .iter_mut()
+            .map(|source| {
+                capture(source.as_mut())
+                    .context(format!("Failed to capture {}", source.description()))
+            })
+            .collect()
+    }
+}
+
+impl PixelSource for CombinedPixelSource {
+    fn description(&self) -> String {
+        self.sources
+            .iter()
+            .map(|source| source.description())
+            .collect::<Vec<_>>()
+            .join(" + ")
+    }
+
+    fn capture(&mut self) -> Result<Frame> {
+        let frames = self.capture_each(|source| source.capture())?;
+
+        Ok(combine_frames(&frames, 1))
+    }
+
+    fn capture_downsampled(
+        &mut self,
+        downsample_factor: &dyn Fn(u32, u32) -> u32,
+    ) -> Result<Frame> {
+        // The factor is chosen for the size of the combined frame, which is only known up front
+        // if every source knows its size.
+        let (width, height) = match self.size() {
+            Some(size) => size,
+            None => return self.capture(),
+        };
+        let factor = downsample_factor(width, height).max(1);
+
+        let frames = self
+            .capture_each(|source| source.capture_downsampled(&|_, _| factor))?
+            .into_iter()
+            .map(|frame| match factor / frame.scale.max(1) {
+                0 | 1 => frame,
+                remaining_factor => box_downsample(&frame, remaining_factor),
+            })
+            .collect::<Vec<_>>();
+
+        Ok(combine_frames(&frames, factor))
+    }
+
+    fn size(&self) -> Option<(u32, u32)> {
+        let sizes = self
+            .sources
+            .iter()
+            .map(|source| source.size())
+            .collect::<Option<Vec<_>>>()?;
+
+        Some((
+            sizes.iter().map(|(width, _)| width).sum(),
+            sizes.iter().map(|(_, height)| *height).max().unwrap_or(0),
+        ))
+    }
+}
+
+/// Places the given frames side by side, excluding the space below frames that are shorter than
+/// the tallest frame. The combined frame gets the given `scale`.
+fn combine_frames(frames: &[Frame], scale: u32) -> Frame {
+    let width = frames.iter().map(|frame| frame.width).sum::<u32>();
+    let height = frames.iter().map(|frame| frame.height).max().unwrap_or(0);
+
+    let mut pixels = vec![RgbColor::new(0, 0, 0); (width * height) as usize];
+    let mut excluded = vec![true; (width * height) as usize];
+
+    let mut x_offset = 0;
+    for frame in frames {
+        for y in 0..frame.height {
+            for x in 0..frame.width {
+                let source_index = (y * frame.width + x) as usize;
+                let index = (y * width + x_offset + x) as usize;
+
+                pixels[index] = frame.pixels[source_index];
+                excluded[index] = frame.is_excluded(source_index);
+            }
+        }
+        x_offset += frame.width;
+    }
+
+    let mut frame = Frame::new(width, height, pixels);
+    frame.exclude_where(|x, y| excluded[(y * width + x) as usize]);
+    frame.scale = scale;
+
+    frame
+}
@@ -22,7 +22,7 @@ pub fn print_title() {
 
     let colored_package_name = chars
         .into_iter()
-        .zip(colors.into_iter())
+        .zip(colors)
         .map(|(c, color)| {
             c.to_string()
                 .on_truecolor(color.red(), color.green(), color.blue())
@@ -8,6 +8,117 @@ use crate::{
 pub type RgbColor = Rgb<u8>;
 pub type HsvColor = Hsv<f64>;
 
+/// A color in the CIE XYZ color space, relative to the D65 white point of sRGB.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct XyzColor {
+    pub x: f64,
+    /// The relative luminance, from 0 (black) to 1 (white).
+    pub y: f64,
+    pub z: f64,
+}
+
+impl XyzColor {
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        Self { x, y, z }
+    }
+}
+
+/// A color in the CIE xyY color space, which separates the chromaticity of a color from its
+/// luminance (as used by, for example, Philips Hue lights).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct XyColor {
+    /// The x chromaticity coordinate, from 0 to 1.
+    pub x: f64,
+    /// The y chromaticity coordinate, from 0 to 1.
+    pub y: f64,
+    /// The relative luminance (Y), from 0 (black) to 1 (white).
+    pub luminance: f64,
+}
+
+impl XyColor {
+    pub fn new(x: f64, y: f64, luminance: f64) -> Self {
+        Self { x, y, luminance }
+    }
+}
+
+/// A color in the CIELAB color space, relative to the D65 white point of sRGB.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LabColor {
+    /// The lightness, from 0 (black) to 100 (white).
+    pub l: f64,
+    /// How green (negative) or red (positive) the color is.
+    pub a: f64,
+    /// How blue (negative) or yellow (positive) the color is.
+    pub b: f64,
+}
+
+impl LabColor {
+    pub fn new(l: f64, a: f64, b: f64) -> Self {
+        Self { l, a, b }
+    }
+}
+
+/// A color in the OKLab color space, in which equal distances look about equally different.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OklabColor {
+    /// The perceived lightness, from 0 (black) to 1 (white).
+    pub l: f64,
+    /// How green (negative) or red (positive) the color is.
+    pub a: f64,
+    /// How blue (negative) or yellow (positive) the color is.
+    pub b: f64,
+}
+
+impl OklabColor {
+    pub fn new(l: f64, a: f64, b: f64) -> Self {
+        Self { l, a, b }
+    }
+}
+
+/// A color in the OKLCH color space, the polar form of OKLab.
+///
+/// Changing the hue keeps the perceived lightness and colorfulness of the color the same, which
+/// is not the case for the hue of HSV colors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OklchColor {
+    /// The perceived lightness, from 0 (black) to 1 (white).
+    pub l: f64,
+    /// How colorful the color is, from 0 (gray) to about 0.37 for the most vivid sRGB colors.
+    pub c: f64,
+    /// The hue, in degrees from 0 to 360.
+    pub h: f64,
+}
+
+impl OklchColor {
+    pub fn new(l: f64, c: f64, h: f64) -> Self {
+        Self { l, c, h }
+    }
+}
+
+/// A color selected from a frame, along with how dominant it is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DominantColor<C = RgbColor> {
+    pub color: C,
+    /// How dominant this color is, from 0 to 1, with the weights of a palette adding up to at
+    /// most 1.
+    ///
+    /// For most algorithms, this is the share of the frame the color represents. Algorithms that
+    /// favour some pixels over others (like `hue_histogram`) weigh the colors by their relative
+    /// prominence instead, so the weight isn't necessarily the share of the frame.
+    pub weight: f64,
+}
+
+impl<C> DominantColor<C> {
+    pub fn new(color: C, weight: f64) -> Self {
+        Self { color, weight }
+    }
+
+    /// Converts the color, keeping its weight.
+    pub fn map<D>(self, f: impl FnOnce(C) -> D) -> DominantColor<D> {
+        DominantColor::new(f(self.color), self.weight)
+    }
+}
+
 pub trait DesktopDyePayload {
     fn to_desktop_dye_payload(&self) -> String;
 }
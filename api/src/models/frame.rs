@@ -0,0 +1,158 @@
+use crate::models::colors::RgbColor;
+
+/// A single captured image, stored as a row-major list of RGB pixels.
+///
+/// Pixels can be marked as excluded, in which case they are kept in place (so the frame keeps
+/// its shape) but are ignored when calculating colors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<RgbColor>,
+    /// For every pixel, whether it is excluded. `None` if no pixels are excluded.
+    pub excluded: Option<Vec<bool>>,
+    /// The factor this frame was downsampled by while it was captured, so every pixel covers
+    /// `scale` by `scale` pixels of the source. 1 if the frame was not downsampled.
+    pub scale: u32,
+}
+
+impl Frame {
+    pub fn new(width: u32, height: u32, pixels: Vec<RgbColor>) -> Self {
+        Self {
+            width,
+            height,
+            pixels,
+            excluded: None,
+            scale: 1,
+        }
+    }
+
+    /// Indicates whether the pixel at the given index is excluded.
+    pub fn is_excluded(&self, index: usize) -> bool {
+        self.excluded
+            .as_ref()
+            .map(|excluded| excluded[index])
+            .unwrap_or(false)
+    }
+
+    /// Marks every pixel for which `predicate(x, y)` returns `true` as excluded.
+    pub fn exclude_where(&mut self, predicate: impl Fn(u32, u32) -> bool) {
+        let width = self.width;
+        let excluded = self
+            .excluded
+            .get_or_insert_with(|| vec![false; self.pixels.len()]);
+
+        for (index, is_excluded) in excluded.iter_mut().enumerate() {
+            let (x, y) = (index as u32 % width, index as u32 / width);
+            if !*is_excluded && predicate(x, y) {
+                *is_excluded = true;
+            }
+        }
+    }
+
+    /// Marks every pixel within the given rectangle as excluded.
+    pub fn exclude_rect(&mut self, rect: &Rect) {
+        let rect = rect.clamp(self.width, self.height);
+
+        self.exclude_where(|x, y| {
+            x >= rect.x && x < rect.x + rect.width && y >= rect.y && y < rect.y + rect.height
+        });
+    }
+
+    /// Returns the size of the source this frame was captured from, before it was downsampled.
+    pub fn source_size(&self) -> (u32, u32) {
+        (
+            self.width.saturating_mul(self.scale),
+            self.height.saturating_mul(self.scale),
+        )
+    }
+
+    /// Returns all pixels that are not excluded.
+    pub fn included_pixels(&self) -> Vec<RgbColor> {
+        match &self.excluded {
+            None => self.pixels.clone(),
+            Some(excluded) => self
+                .pixels
+                .iter()
+                .zip(excluded)
+                .filter(|(_, is_excluded)| !**is_excluded)
+                .map(|(pixel, _)| *pixel)
+                .collect(),
+        }
+    }
+
+    /// Returns a new frame containing only the pixels within the given rectangle.
+    ///
+    /// The rectangle is clamped to the bounds of this frame.
+    pub fn crop(&self, rect: &Rect) -> Frame {
+        let rect = rect.clamp(self.width, self.height);
+        let rows = (rect.y..rect.y + rect.height).map(|y| {
+            let row_start = (y * self.width + rect.x) as usize;
+            row_start..row_start + rect.width as usize
+        });
+
+        let pixels = rows
+            .clone()
+            .flat_map(|row| self.pixels[row].iter().copied())
+            .collect::<Vec<_>>();
+        let excluded = self.excluded.as_ref().map(|excluded| {
+            rows.flat_map(|row| excluded[row].iter().copied())
+                .collect::<Vec<_>>()
+        });
+
+        Frame {
+            width: rect.width,
+            height: rect.height,
+            pixels,
+            excluded,
+            scale: self.scale,
+        }
+    }
+}
+
+/// A rectangular area of a frame, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Maps this rectangle onto a frame that was downsampled by the given factor, covering every
+    /// pixel this rectangle (partly) covers.
+    pub fn downscale(&self, factor: u32) -> Rect {
+        let factor = factor.max(1);
+        let (x, y) = (self.x / factor, self.y / factor);
+
+        Rect::new(
+            x,
+            y,
+            self.x.saturating_add(self.width).div_ceil(factor) - x,
+            self.y.saturating_add(self.height).div_ceil(factor) - y,
+        )
+    }
+
+    /// Returns the part of this rectangle that lies within a frame of the given size.
+    pub fn clamp(&self, frame_width: u32, frame_height: u32) -> Rect {
+        let x = self.x.min(frame_width);
+        let y = self.y.min(frame_height);
+
+        Rect::new(
+            x,
+            y,
+            self.width.min(frame_width - x),
+            self.height.min(frame_height - y),
+        )
+    }
+}
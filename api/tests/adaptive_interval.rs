@@ -0,0 +1,77 @@
+use desktop_dye_api::{config::DesktopDyeConfig, functions::AdaptiveInterval};
+
+const MIN_INTERVAL: f64 = 0.5;
+const MAX_INTERVAL: f64 = 10.0;
+
+fn adaptive_interval(initial_interval: f64) -> AdaptiveInterval {
+    AdaptiveInterval::new(initial_interval, MIN_INTERVAL, MAX_INTERVAL, 20.0)
+}
+
+/// Parses a config with the given capture interval setting.
+fn config_with(setting: &str) -> anyhow::Result<DesktopDyeConfig> {
+    DesktopDyeConfig::from_yaml(&format!(
+        "
+ha_endpoint: http://localhost:8123
+ha_token: token
+ha_target_entity_id: light.test
+{}
+",
+        setting
+    ))
+}
+
+#[test]
+fn activity_shrinks_the_interval_right_away() {
+    let mut interval = adaptive_interval(5.0);
+
+    assert_eq!(interval.update(1.0), MIN_INTERVAL);
+
+    // A difference of 0.025 is half of the full activity at a sensitivity of 20.
+    let mut interval = adaptive_interval(MAX_INTERVAL);
+    assert_eq!(interval.update(0.025), 5.25);
+}
+
+#[test]
+fn the_interval_grows_gradually_while_idle() {
+    let mut interval = adaptive_interval(1.0);
+
+    assert_eq!(interval.update(0.0), 1.5);
+    assert_eq!(interval.update(0.0), 2.25);
+    assert_eq!(interval.interval(), 2.25);
+}
+
+#[test]
+fn the_interval_stays_within_the_min_and_max() {
+    let mut interval = adaptive_interval(100.0);
+    assert_eq!(interval.interval(), MAX_INTERVAL);
+    for _ in 0..20 {
+        assert!(interval.update(0.0) <= MAX_INTERVAL);
+    }
+    assert_eq!(interval.interval(), MAX_INTERVAL);
+
+    let mut interval = adaptive_interval(0.0);
+    assert_eq!(interval.interval(), MIN_INTERVAL);
+    // Differences larger than needed for the full activity don't go below the minimum.
+    assert_eq!(interval.update(1.0), MIN_INTERVAL);
+}
+
+#[test]
+fn non_finite_settings_are_rejected() {
+    assert!(config_with("capture_interval_sensitivity: 5").is_ok());
+
+    for value in ["0", "-1", ".nan", ".inf"] {
+        for name in [
+            "capture_interval_sensitivity",
+            "capture_interval",
+            "min_capture_interval",
+            "max_capture_interval",
+        ] {
+            assert!(
+                config_with(&format!("{}: {}", name, value)).is_err(),
+                "{} of {} was accepted",
+                name,
+                value
+            );
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use desktop_dye_api::{
+    functions::{ToLab, ToOklab, ToOklch, ToRgb, ToXy, ToXyz},
+    models::colors::{LabColor, RgbColor},
+};
+
+const EPSILON: f64 = 1e-3;
+
+fn assert_close(actual: [f64; 3], expected: [f64; 3]) {
+    for (actual_component, expected_component) in actual.iter().zip(expected) {
+        assert!(
+            (actual_component - expected_component).abs() < EPSILON,
+            "expected {:?}, found {:?}",
+            expected,
+            actual
+        );
+    }
+}
+
+#[test]
+fn conversions_match_reference_values() {
+    let red = RgbColor::new(255, 0, 0);
+
+    let xyz = red.to_xyz();
+    assert_close([xyz.x, xyz.y, xyz.z], [0.4125, 0.2127, 0.0193]);
+
+    let xy = red.to_xy();
+    assert_close([xy.x, xy.y, xy.luminance], [0.64, 0.33, 0.2127]);
+
+    let lab = red.to_lab();
+    assert_close([lab.l, lab.a, lab.b], [53.241, 80.092, 67.203]);
+
+    let oklab = red.to_oklab();
+    assert_close([oklab.l, oklab.a, oklab.b], [0.628, 0.2249, 0.1258]);
+
+    let oklch = red.to_oklch();
+    assert_close([oklch.l, oklch.c, oklch.h], [0.628, 0.2577, 29.234]);
+}
+
+#[test]
+fn black_and_white_have_the_white_point_chromaticity() {
+    for rgb in [RgbColor::new(0, 0, 0), RgbColor::new(255, 255, 255)] {
+        let xy = rgb.to_xy();
+        assert_close([xy.x, xy.y, 0.0], [0.3127, 0.329, 0.0]);
+
+        let lab = rgb.to_lab();
+        assert!(lab.a.abs() < EPSILON && lab.b.abs() < EPSILON);
+    }
+}
+
+#[test]
+fn conversions_round_trip_to_the_same_rgb_color() {
+    for red in (0..=255).step_by(15) {
+        for green in (0..=255).step_by(15) {
+            for blue in (0..=255).step_by(15) {
+                let rgb = RgbColor::new(red, green, blue);
+
+                assert_eq!(rgb.to_xyz().to_rgb(), rgb);
+                assert_eq!(rgb.to_xy().to_rgb(), rgb);
+                assert_eq!(rgb.to_lab().to_rgb(), rgb);
+                assert_eq!(rgb.to_oklab().to_rgb(), rgb);
+                assert_eq!(rgb.to_oklch().to_rgb(), rgb);
+            }
+        }
+    }
+}
+
+#[test]
+fn perceptual_colors_convert_between_each_other_without_rounding() {
+    // A color between two 8-bit sRGB colors, and one outside of the sRGB gamut.
+    for lab in [
+        LabColor::new(60.0, 20.0, -30.0),
+        LabColor::new(50.0, 120.0, -90.0),
+    ] {
+        let round_trips = [
+            lab.to_lab(),
+            lab.to_xyz().to_lab(),
+            lab.to_xy().to_lab(),
+            lab.to_oklab().to_lab(),
+            lab.to_oklch().to_lab(),
+            lab.to_oklch().to_xy().to_oklab().to_lab(),
+        ];
+
+        // The published matrices are not exact inverses of each other, but the error is far
+        // below that of rounding to 8-bit sRGB (up to about 0.5).
+        for round_trip in round_trips {
+            assert!(
+                (round_trip.l - lab.l).abs() < EPSILON
+                    && (round_trip.a - lab.a).abs() < EPSILON
+                    && (round_trip.b - lab.b).abs() < EPSILON,
+                "expected {:?}, found {:?}",
+                lab,
+                round_trip
+            );
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use desktop_dye_api::{
+    functions::{cie76, cie94, ciede2000, ColorDistance, ToLab},
+    models::colors::{LabColor, RgbColor},
+};
+
+/// The test data published with "The CIEDE2000 Color-Difference Formula: Implementation Notes,
+/// Supplementary Test Data, and Mathematical Observations" by Sharma, Wu and Dalal, as pairs of
+/// CIELAB colors and their expected difference.
+const CIEDE2000_TEST_DATA: [([f64; 3], [f64; 3], f64); 34] = [
+    ([50.0, 2.6772, -79.7751], [50.0, 0.0, -82.7485], 2.0425),
+    ([50.0, 3.1571, -77.2803], [50.0, 0.0, -82.7485], 2.8615),
+    ([50.0, 2.8361, -74.0200], [50.0, 0.0, -82.7485], 3.4412),
+    ([50.0, -1.3802, -84.2814], [50.0, 0.0, -82.7485], 1.0000),
+    ([50.0, -1.1848, -84.8006], [50.0, 0.0, -82.7485], 1.0000),
+    ([50.0, -0.9009, -85.5211], [50.0, 0.0, -82.7485], 1.0000),
+    ([50.0, 0.0, 0.0], [50.0, -1.0, 2.0], 2.3669),
+    ([50.0, -1.0, 2.0], [50.0, 0.0, 0.0], 2.3669),
+    ([50.0, 2.4900, -0.0010], [50.0, -2.4900, 0.0009], 7.1792),
+    ([50.0, 2.4900, -0.0010], [50.0, -2.4900, 0.0010], 7.1792),
+    ([50.0, 2.4900, -0.0010], [50.0, -2.4900, 0.0011], 7.2195),
+    ([50.0, 2.4900, -0.0010], [50.0, -2.4900, 0.0012], 7.2195),
+    ([50.0, -0.0010, 2.4900], [50.0, 0.0009, -2.4900], 4.8045),
+    ([50.0, -0.0010, 2.4900], [50.0, 0.0010, -2.4900], 4.8045),
+    ([50.0, -0.0010, 2.4900], [50.0, 0.0011, -2.4900], 4.7461),
+    ([50.0, 2.5, 0.0], [50.0, 0.0, -2.5], 4.3065),
+    ([50.0, 2.5, 0.0], [73.0, 25.0, -18.0], 27.1492),
+    ([50.0, 2.5, 0.0], [61.0, -5.0, 29.0], 22.8977),
+    ([50.0, 2.5, 0.0], [56.0, -27.0, -3.0], 31.9030),
+    ([50.0, 2.5, 0.0], [58.0, 24.0, 15.0], 19.4535),
+    ([50.0, 2.5, 0.0], [50.0, 3.1736, 0.5854], 1.0000),
+    ([50.0, 2.5, 0.0], [50.0, 3.2972, 0.0], 1.0000),
+    ([50.0, 2.5, 0.0], [50.0, 1.8634, 0.5757], 1.0000),
+    ([50.0, 2.5, 0.0], [50.0, 3.2592, 0.3350], 1.0000),
+    (
+        [60.2574, -34.0099, 36.2677],
+        [60.4626, -34.1751, 39.4387],
+        1.2644,
+    ),
+    (
+        [63.0109, -31.0961, -5.8663],
+        [62.8187, -29.7946, -4.0864],
+        1.2630,
+    ),
+    (
+        [61.2901, 3.7196, -5.3901],
+        [61.4292, 2.2480, -4.9620],
+        1.8731,
+    ),
+    (
+        [35.0831, -44.1164, 3.7933],
+        [35.0232, -40.0716, 1.5901],
+        1.8645,
+    ),
+    (
+        [22.7233, 20.0904, -46.6940],
+        [23.0331, 14.9730, -42.5619],
+        2.0373,
+    ),
+    (
+        [36.4612, 47.8580, 18.3852],
+        [36.2715, 50.5065, 21.2231],
+        1.4146,
+    ),
+    (
+        [90.8027, -2.0831, 1.4410],
+        [91.1528, -1.6435, 0.0447],
+        1.4441,
+    ),
+    (
+        [90.9257, -0.5406, -0.9208],
+        [88.6381, -0.8985, -0.7239],
+        1.5381,
+    ),
+    (
+        [6.7747, -0.2908, -2.4247],
+        [5.8714, -0.0985, -2.2286],
+        0.6377,
+    ),
+    (
+        [2.0776, 0.0795, -1.1350],
+        [0.9033, -0.0636, -0.5514],
+        0.9082,
+    ),
+];
+
+/// The expected differences are rounded to 4 decimals.
+const EPSILON: f64 = 1e-4;
+
+fn lab([l, a, b]: [f64; 3]) -> LabColor {
+    LabColor::new(l, a, b)
+}
+
+#[test]
+fn ciede2000_matches_published_test_data() {
+    for (i, (a, b, expected)) in CIEDE2000_TEST_DATA.iter().enumerate() {
+        for difference in [ciede2000(&lab(*a), &lab(*b)), ciede2000(&lab(*b), &lab(*a))] {
+            assert!(
+                (difference - expected).abs() < EPSILON,
+                "pair {}: expected {}, found {}",
+                i + 1,
+                expected,
+                difference
+            );
+        }
+    }
+}
+
+#[test]
+fn cie76_and_cie94_agree_on_lightness_differences() {
+    let (a, b) = (lab([50.0, 20.0, -30.0]), lab([53.0, 20.0, -30.0]));
+    assert!((cie76(&a, &b) - 3.0).abs() < EPSILON);
+    assert!((cie94(&a, &b) - 3.0).abs() < EPSILON);
+
+    // CIE94 reduces the chroma and hue differences of vivid colors.
+    let (a, b) = (lab([50.0, 60.0, 0.0]), lab([50.0, 56.0, 3.0]));
+    assert!((cie76(&a, &b) - 5.0).abs() < EPSILON);
+    assert!(cie94(&a, &b) < cie76(&a, &b));
+}
+
+#[test]
+fn color_distance_converts_colors_to_the_metric_color_space() {
+    let (a, b) = (RgbColor::new(200, 30, 40), RgbColor::new(190, 60, 30));
+
+    assert_eq!(
+        ColorDistance::Ciede2000.distance(&a, &b),
+        ciede2000(&a.to_lab(), &b.to_lab())
+    );
+    assert_eq!(
+        ColorDistance::Cie76.distance(&a, &b),
+        cie76(&a.to_lab(), &b.to_lab())
+    );
+    assert_eq!(
+        ColorDistance::Cie94.distance(&a, &b),
+        cie94(&a.to_lab(), &b.to_lab())
+    );
+
+    let (black, white) = (RgbColor::new(0, 0, 0), RgbColor::new(255, 255, 255));
+    assert!((ColorDistance::Oklab.distance(&black, &white) - 1.0).abs() < 1e-3);
+    assert!((ColorDistance::Cie76.distance(&black, &white) - 100.0).abs() < 1e-3);
+
+    for metric in [
+        ColorDistance::Cie76,
+        ColorDistance::Cie94,
+        ColorDistance::Ciede2000,
+        ColorDistance::Oklab,
+    ] {
+        assert_eq!(metric.distance(&a, &a), 0.0, "{}", metric);
+    }
+}
@@ -0,0 +1,135 @@
+use angular_units::Deg;
+use desktop_dye_api::{
+    config::{default_color_grading, ColorGradingStep},
+    functions::{apply_grading_step, grade_color},
+    models::colors::HsvColor,
+};
+
+const EPSILON: f64 = 1e-6;
+
+fn assert_hsv_eq(actual: HsvColor, expected: HsvColor) {
+    assert!(
+        (actual.hue().0 - expected.hue().0).abs() < EPSILON
+            && (actual.saturation() - expected.saturation()).abs() < EPSILON
+            && (actual.value() - expected.value()).abs() < EPSILON,
+        "expected {:?}, got {:?}",
+        expected,
+        actual
+    );
+}
+
+#[test]
+fn default_pipeline_boosts_saturation_and_value() {
+    let graded = grade_color(
+        HsvColor::new(Deg(120.0), 0.5, 0.9),
+        &default_color_grading(),
+    );
+
+    assert_hsv_eq(graded, HsvColor::new(Deg(120.0), 0.7, 1.0));
+}
+
+#[test]
+fn empty_pipeline_keeps_colors() {
+    let color = HsvColor::new(Deg(200.0), 0.3, 0.4);
+
+    assert_hsv_eq(grade_color(color, &[]), color);
+}
+
+#[test]
+fn steps_are_applied_in_order() {
+    let color = HsvColor::new(Deg(0.0), 1.0, 0.4);
+    let gain_then_offset = [
+        ColorGradingStep::Value {
+            gain: 2.0,
+            offset: 0.0,
+        },
+        ColorGradingStep::Value {
+            gain: 1.0,
+            offset: 0.1,
+        },
+    ];
+    let offset_then_gain = [gain_then_offset[1].clone(), gain_then_offset[0].clone()];
+
+    assert!((grade_color(color, &gain_then_offset).value() - 0.9).abs() < EPSILON);
+    assert!((grade_color(color, &offset_then_gain).value() - 1.0).abs() < EPSILON);
+}
+
+#[test]
+fn gamma_contrast_and_clamp_adjust_brightness() {
+    let color = HsvColor::new(Deg(30.0), 0.5, 0.25);
+
+    let gamma = apply_grading_step(color, &ColorGradingStep::Gamma { gamma: 2.0 });
+    assert_hsv_eq(gamma, HsvColor::new(Deg(30.0), 0.5, 0.5));
+
+    let contrast = apply_grading_step(color, &ColorGradingStep::Contrast { amount: 2.0 });
+    assert_hsv_eq(contrast, HsvColor::new(Deg(30.0), 0.5, 0.0));
+
+    let clamp = apply_grading_step(
+        color,
+        &ColorGradingStep::BrightnessClamp { min: 0.3, max: 0.8 },
+    );
+    assert_hsv_eq(clamp, HsvColor::new(Deg(30.0), 0.5, 0.3));
+}
+
+#[test]
+fn white_balance_scales_rgb_channels() {
+    // Pure white, with the blue channel halved, becomes a light yellow.
+    let graded = apply_grading_step(
+        HsvColor::new(Deg(0.0), 0.0, 1.0),
+        &ColorGradingStep::WhiteBalance {
+            red: 1.0,
+            green: 1.0,
+            blue: 0.5,
+        },
+    );
+
+    assert_hsv_eq(graded, HsvColor::new(Deg(60.0), 0.5, 1.0));
+}
+
+#[test]
+fn steps_are_read_from_a_tagged_list() {
+    let steps = serde_yaml::from_str::<Vec<ColorGradingStep>>(
+        "- step: saturation\n  offset: 0.2\n- step: gamma\n- step: white_balance\n  blue: 0.9\n",
+    )
+    .unwrap();
+
+    assert_eq!(
+        steps,
+        vec![
+            ColorGradingStep::Saturation {
+                gain: 1.0,
+                offset: 0.2
+            },
+            ColorGradingStep::Gamma { gamma: 1.0 },
+            ColorGradingStep::WhiteBalance {
+                red: 1.0,
+                green: 1.0,
+                blue: 0.9
+            },
+        ]
+    );
+}
+
+#[test]
+fn invalid_steps_are_reported() {
+    assert!(ColorGradingStep::Gamma { gamma: 0.0 }
+        .validate("Step 1")
+        .contains(&"Step 1 gamma must be greater than 0. Found 0".to_string()));
+    assert_eq!(
+        ColorGradingStep::BrightnessClamp { min: 0.8, max: 0.2 }
+            .validate("Step 2")
+            .len(),
+        1
+    );
+    assert!(ColorGradingStep::Contrast { amount: 1.5 }
+        .validate("Step 3")
+        .is_empty());
+
+    let non_finite_offsets = serde_yaml::from_str::<Vec<ColorGradingStep>>(
+        "- step: saturation\n  offset: .nan\n- step: value\n  offset: .inf\n",
+    )
+    .unwrap();
+    for step in non_finite_offsets {
+        assert_eq!(step.validate("Step 4").len(), 1);
+    }
+}
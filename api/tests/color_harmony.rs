@@ -0,0 +1,117 @@
+mod common;
+
+use desktop_dye_api::{
+    config::ColorSelectionMode,
+    functions::{harmonize_colors, harmony_hue_offsets, ToOklch, ToRgb},
+    models::colors::{OklchColor, RgbColor},
+};
+
+use common::palette;
+
+/// The largest hue difference (in degrees) allowed after rounding a color to 8-bit channels.
+const HUE_TOLERANCE: f64 = 2.0;
+/// The largest lightness difference allowed after rounding a color to 8-bit channels.
+const LIGHTNESS_TOLERANCE: f64 = 0.01;
+
+fn hue_difference(a: f64, b: f64) -> f64 {
+    let difference = (a - b).rem_euclid(360.0);
+    difference.min(360.0 - difference)
+}
+
+#[test]
+fn harmony_modes_rotate_the_primary_hue() {
+    let expected_offsets = [
+        (ColorSelectionMode::Complementary, vec![0.0, 180.0]),
+        (ColorSelectionMode::Triadic, vec![0.0, 120.0, 240.0]),
+        (ColorSelectionMode::Analogous, vec![0.0, -20.0, 20.0]),
+        (
+            ColorSelectionMode::SplitComplementary,
+            vec![0.0, 140.0, 220.0],
+        ),
+    ];
+
+    for (mode, expected) in expected_offsets {
+        let offsets = harmony_hue_offsets(&mode, 20.0, 40.0).unwrap();
+        assert_eq!(offsets, expected, "{}", mode);
+
+        let primary = (60, 90, 140);
+        let colors = harmonize_colors(
+            palette(&vec![primary; offsets.len()]),
+            &offsets,
+            offsets.len(),
+        );
+        let primary_hue = colors[0].color.to_oklch().h;
+
+        for (color, offset) in colors.iter().zip(&offsets) {
+            let hue = color.color.to_oklch().h;
+            assert!(
+                hue_difference(hue, primary_hue + offset) < HUE_TOLERANCE,
+                "{}: expected hue {}, found {}",
+                mode,
+                primary_hue + offset,
+                hue
+            );
+        }
+    }
+
+    assert!(harmony_hue_offsets(&ColorSelectionMode::HueShift, 20.0, 40.0).is_none());
+}
+
+#[test]
+fn harmonized_colors_keep_primary_color_lightness_and_weights() {
+    let primary = (200, 40, 60);
+    let mut colors = palette(&[primary; 5]);
+    for (index, color) in colors.iter_mut().enumerate() {
+        color.weight = 1.0 / (index + 1) as f64;
+    }
+    let offsets = harmony_hue_offsets(&ColorSelectionMode::Triadic, 30.0, 30.0).unwrap();
+    let harmonized = harmonize_colors(colors.clone(), &offsets, colors.len());
+
+    assert_eq!(harmonized.len(), colors.len());
+    assert_eq!(harmonized[0], colors[0]);
+    // The offsets are repeated when there are more colors than offsets.
+    assert_eq!(harmonized[3].color, harmonized[0].color);
+    assert_eq!(harmonized[4].color, harmonized[1].color);
+
+    let primary_lightness = colors[0].color.to_oklch().l;
+    for (harmonized, original) in harmonized.iter().zip(&colors) {
+        assert_eq!(harmonized.weight, original.weight);
+        assert!((harmonized.color.to_oklch().l - primary_lightness).abs() < LIGHTNESS_TOLERANCE);
+    }
+}
+
+#[test]
+fn every_harmony_color_is_sent_for_a_single_dominant_color() {
+    let colors = palette(&[(60, 90, 140)]);
+    let offsets = harmony_hue_offsets(&ColorSelectionMode::Triadic, 30.0, 30.0).unwrap();
+
+    let harmonized = harmonize_colors(colors.clone(), &offsets, 1);
+    assert_eq!(harmonized.len(), offsets.len());
+    assert_eq!(harmonized[0], colors[0]);
+    let primary_hue = colors[0].color.to_oklch().h;
+    for (color, offset) in harmonized.iter().zip(&offsets) {
+        assert!(hue_difference(color.color.to_oklch().h, primary_hue + offset) < HUE_TOLERANCE);
+        // Colors without a dominant color at their position take the weight of the primary.
+        assert_eq!(color.weight, colors[0].weight);
+    }
+
+    // The colors are repeated to fill the sample size.
+    let harmonized = harmonize_colors(colors, &offsets, 5);
+    assert_eq!(harmonized.len(), 5);
+    assert_eq!(harmonized[3].color, harmonized[0].color);
+    assert_eq!(harmonized[4].color, harmonized[1].color);
+}
+
+#[test]
+fn out_of_gamut_colors_keep_their_lightness_and_hue() {
+    // The chroma of the most vivid cyan in sRGB is far below 0.3.
+    let color = OklchColor::new(0.8, 0.3, 200.0);
+    let mapped = color.to_rgb().to_oklch();
+
+    assert!(mapped.c < color.c);
+    assert!((mapped.l - color.l).abs() < LIGHTNESS_TOLERANCE);
+    assert!(hue_difference(mapped.h, color.h) < HUE_TOLERANCE);
+
+    let rgb = RgbColor::new(12, 200, 90);
+    assert_eq!(rgb.to_oklch().to_rgb(), rgb);
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use desktop_dye_api::{
+    config::{Smoothing, SmoothingMethod},
+    functions::{ColorSmoother, ToOklab, ToRgb},
+    models::colors::{DominantColor, HsvColor, RgbColor},
+};
+
+use common::palette;
+
+fn smoother(method: SmoothingMethod) -> ColorSmoother {
+    ColorSmoother::new(Smoothing {
+        method,
+        time_constant: 1.0,
+        send_rate: None,
+    })
+}
+
+#[test]
+fn first_palette_is_used_at_once() {
+    for method in [SmoothingMethod::Ema, SmoothingMethod::Spring] {
+        let mut smoother = smoother(method);
+        let red = palette(&[(255, 0, 0)]);
+
+        smoother.set_target(&red);
+
+        assert!(smoother.is_settled());
+        assert_eq!(smoother.advance(0.1), red);
+    }
+}
+
+#[test]
+fn colors_move_gradually_and_settle_on_the_target() {
+    for method in [SmoothingMethod::Ema, SmoothingMethod::Spring] {
+        let mut smoother = smoother(method);
+        let red = palette(&[(255, 0, 0)]);
+        let blue = palette(&[(0, 0, 255)]);
+        smoother.set_target(&red);
+        smoother.set_target(&blue);
+
+        let start = red[0].color.to_oklab();
+        let end = blue[0].color.to_oklab();
+        let progress = |colors: &[DominantColor<HsvColor>]| {
+            let current = colors[0].color.to_oklab();
+            (current.b - start.b) / (end.b - start.b)
+        };
+
+        let mut last_progress = 0.0;
+        for _ in 0..4 {
+            let progress = progress(&smoother.advance(0.25));
+            assert!(
+                progress > last_progress && progress < 1.0,
+                "{:?}: {} after {}",
+                method,
+                progress,
+                last_progress
+            );
+            last_progress = progress;
+        }
+        // After one time constant, the colors have moved most of the way.
+        assert!(last_progress > 0.5 && last_progress < 0.7, "{:?}", method);
+
+        let mut colors = vec![];
+        for _ in 0..100 {
+            colors = smoother.advance(0.25);
+        }
+        assert!(smoother.is_settled());
+        assert_eq!(colors, blue);
+    }
+}
+
+#[test]
+fn palette_size_changes_are_used_at_once() {
+    let mut smoother = smoother(SmoothingMethod::Ema);
+    smoother.set_target(&palette(&[(255, 0, 0)]));
+
+    let two_colors = palette(&[(0, 255, 0), (0, 0, 255)]);
+    smoother.set_target(&two_colors);
+
+    assert_eq!(smoother.advance(0.1), two_colors);
+}
+
+#[test]
+fn colors_that_swap_positions_keep_their_value() {
+    for method in [SmoothingMethod::Ema, SmoothingMethod::Spring] {
+        let mut smoother = smoother(method);
+        smoother.set_target(&palette(&[(255, 0, 0), (0, 0, 255)]));
+
+        let swapped = palette(&[(0, 0, 255), (255, 0, 0)]);
+        smoother.set_target(&swapped);
+
+        assert!(smoother.is_settled(), "{:?}", method);
+        assert_eq!(smoother.advance(0.1), swapped);
+    }
+}
+
+#[test]
+fn new_colors_replace_the_nearest_current_color() {
+    let mut smoother = smoother(SmoothingMethod::Ema);
+    smoother.set_target(&palette(&[(255, 0, 0), (0, 0, 255)]));
+
+    // The second color stays blue, so only the first color moves from red to orange.
+    smoother.set_target(&palette(&[(0, 0, 255), (255, 128, 0)]));
+    let colors = smoother.advance(0.25);
+
+    assert_eq!(colors[0].color.to_rgb(), RgbColor::new(0, 0, 255));
+    let moving = colors[1].color.to_rgb();
+    assert!(
+        moving.red() == 255 && moving.green() > 0 && moving.green() < 128 && moving.blue() == 0,
+        "{:?}",
+        moving
+    );
+}
@@ -0,0 +1,69 @@
+// Not every test uses every helper.
+#![allow(dead_code)]
+
+use anyhow::Result;
+use desktop_dye_api::{
+    functions::{PixelSource, ToHsv},
+    models::{
+        colors::{DominantColor, HsvColor, RgbColor},
+        frame::Frame,
+    },
+};
+use png::{BitDepth, ColorType};
+
+/// Returns a palette of the given RGB colors, which all have the same weight.
+pub fn palette(colors: &[(u8, u8, u8)]) -> Vec<DominantColor<HsvColor>> {
+    colors
+        .iter()
+        .map(|(red, green, blue)| {
+            DominantColor::new(
+                RgbColor::new(*red, *green, *blue).to_hsv(),
+                1.0 / colors.len() as f64,
+            )
+        })
+        .collect()
+}
+
+/// Encodes a PNG image of the given size, color type and bit depth, with the given palette and
+/// transparency chunk for indexed images.
+pub fn encode_png(
+    width: u32,
+    height: u32,
+    color_type: ColorType,
+    bit_depth: BitDepth,
+    data: &[u8],
+    palette: Option<(&[u8], &[u8])>,
+) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut buffer, width, height);
+        encoder.set_color(color_type);
+        encoder.set_depth(bit_depth);
+        if let Some((palette, transparency)) = palette {
+            encoder.set_palette(palette.to_vec());
+            encoder.set_trns(transparency.to_vec());
+        }
+
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(data).unwrap();
+    }
+
+    buffer
+}
+
+/// A pixel source that always returns the same frame, without downsampling it while capturing.
+pub struct StaticPixelSource(pub Frame);
+
+impl PixelSource for StaticPixelSource {
+    fn description(&self) -> String {
+        "a static frame".to_string()
+    }
+
+    fn capture(&mut self) -> Result<Frame> {
+        Ok(self.0.clone())
+    }
+
+    fn size(&self) -> Option<(u32, u32)> {
+        Some((self.0.width, self.0.height))
+    }
+}
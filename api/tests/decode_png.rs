@@ -0,0 +1,134 @@
+mod common;
+
+use desktop_dye_api::{
+    functions::decode_png_pixels,
+    models::{colors::RgbColor, frame::Frame},
+};
+use png::{BitDepth, ColorType};
+
+use common::encode_png;
+
+fn gray(value: u8) -> RgbColor {
+    RgbColor::new(value, value, value)
+}
+
+fn excluded(frame: &Frame) -> Vec<bool> {
+    (0..frame.pixels.len())
+        .map(|index| frame.is_excluded(index))
+        .collect()
+}
+
+#[test]
+fn grayscale_images_are_decoded_as_gray_pixels() {
+    let png = encode_png(
+        2,
+        2,
+        ColorType::Grayscale,
+        BitDepth::Eight,
+        &[0, 64, 128, 255],
+        None,
+    );
+
+    let frame = decode_png_pixels(&png).unwrap();
+
+    assert_eq!((frame.width, frame.height), (2, 2));
+    assert_eq!(frame.pixels, vec![gray(0), gray(64), gray(128), gray(255)]);
+    assert_eq!(frame.excluded, None);
+}
+
+#[test]
+fn grayscale_alpha_images_are_composited_on_black() {
+    let png = encode_png(
+        2,
+        2,
+        ColorType::GrayscaleAlpha,
+        BitDepth::Eight,
+        &[200, 255, 200, 128, 200, 0, 100, 255],
+        None,
+    );
+
+    let frame = decode_png_pixels(&png).unwrap();
+
+    assert_eq!(frame.pixels, vec![gray(200), gray(100), gray(0), gray(100)]);
+    assert_eq!(excluded(&frame), vec![false, false, true, false]);
+}
+
+#[test]
+fn indexed_images_are_expanded_with_their_transparency() {
+    let palette = [255, 0, 0, 0, 255, 0, 0, 0, 255];
+    // The first entry is opaque, the second fully transparent and the third is left opaque.
+    let transparency = [255, 0];
+    let png = encode_png(
+        2,
+        2,
+        ColorType::Indexed,
+        BitDepth::Eight,
+        &[0, 1, 2, 0],
+        Some((&palette, &transparency)),
+    );
+
+    let frame = decode_png_pixels(&png).unwrap();
+
+    assert_eq!(
+        frame.pixels,
+        vec![
+            RgbColor::new(255, 0, 0),
+            RgbColor::new(0, 0, 0),
+            RgbColor::new(0, 0, 255),
+            RgbColor::new(255, 0, 0),
+        ]
+    );
+    assert_eq!(excluded(&frame), vec![false, true, false, false]);
+}
+
+#[test]
+fn sixteen_bit_images_are_reduced_to_eight_bits() {
+    let data = [
+        [0xff, 0xff, 0x00, 0x00, 0x00, 0x00],
+        [0x00, 0x00, 0x80, 0x7f, 0x00, 0x00],
+        [0x00, 0x00, 0x00, 0x00, 0x12, 0x34],
+        [0x40, 0x01, 0x40, 0x01, 0x40, 0x01],
+    ]
+    .concat();
+    let png = encode_png(2, 2, ColorType::Rgb, BitDepth::Sixteen, &data, None);
+
+    let frame = decode_png_pixels(&png).unwrap();
+
+    // Only the most significant byte of every channel is kept.
+    assert_eq!(
+        frame.pixels,
+        vec![
+            RgbColor::new(255, 0, 0),
+            RgbColor::new(0, 128, 0),
+            RgbColor::new(0, 0, 0x12),
+            gray(0x40),
+        ]
+    );
+    assert_eq!(frame.excluded, None);
+}
+
+#[test]
+fn fully_transparent_rgba_pixels_are_excluded() {
+    let data = [
+        [255, 0, 0, 255],
+        [0, 255, 0, 0],
+        [0, 0, 255, 51],
+        [255, 255, 255, 0],
+    ]
+    .concat();
+    let png = encode_png(2, 2, ColorType::Rgba, BitDepth::Eight, &data, None);
+
+    let frame = decode_png_pixels(&png).unwrap();
+
+    assert_eq!(
+        frame.pixels,
+        vec![
+            RgbColor::new(255, 0, 0),
+            RgbColor::new(0, 0, 0),
+            RgbColor::new(0, 0, 51),
+            RgbColor::new(0, 0, 0),
+        ]
+    );
+    assert_eq!(excluded(&frame), vec![false, true, false, true]);
+    assert_eq!(frame.included_pixels().len(), 2);
+}
@@ -0,0 +1,95 @@
+use desktop_dye_api::{
+    functions::deduplicate_colors,
+    models::colors::{DominantColor, RgbColor},
+};
+
+/// Returns a palette of the given RGB colors with the given weights.
+fn weighted_palette(colors: &[((u8, u8, u8), f64)]) -> Vec<DominantColor> {
+    colors
+        .iter()
+        .map(|((red, green, blue), weight)| {
+            DominantColor::new(RgbColor::new(*red, *green, *blue), *weight)
+        })
+        .collect()
+}
+
+fn weights(colors: &[DominantColor]) -> Vec<f64> {
+    colors.iter().map(|color| color.weight).collect()
+}
+
+#[test]
+fn close_colors_are_merged_into_the_closest_kept_color() {
+    // The third color is within the minimum distance of both others, but closest to the second.
+    let candidates = weighted_palette(&[
+        ((200, 40, 40), 0.4),
+        ((200, 40, 90), 0.3),
+        ((200, 40, 70), 0.2),
+        ((205, 42, 42), 0.1),
+    ]);
+
+    let colors = deduplicate_colors(candidates.clone(), 10.0, 10);
+
+    assert_eq!(colors.len(), 2);
+    assert_eq!(colors[0].color, candidates[0].color);
+    assert_eq!(colors[1].color, candidates[1].color);
+    // The weights of merged colors are summed.
+    assert!((colors[0].weight - 0.5).abs() < f64::EPSILON);
+    assert!((colors[1].weight - 0.5).abs() < f64::EPSILON);
+}
+
+#[test]
+fn distinct_colors_are_kept() {
+    let candidates = weighted_palette(&[
+        ((200, 40, 40), 0.5),
+        ((40, 200, 40), 0.3),
+        ((40, 40, 200), 0.2),
+    ]);
+
+    assert_eq!(deduplicate_colors(candidates.clone(), 10.0, 10), candidates);
+}
+
+#[test]
+fn at_most_max_count_colors_are_kept() {
+    let candidates = weighted_palette(&[
+        ((200, 40, 40), 0.4),
+        ((40, 200, 40), 0.3),
+        // Merged into the closest kept colors (red and green), since two colors are kept already.
+        ((40, 40, 200), 0.2),
+        ((220, 220, 40), 0.06),
+        // Merged into the first color, being within the minimum distance of it.
+        ((205, 42, 42), 0.04),
+    ]);
+
+    let colors = deduplicate_colors(candidates.clone(), 10.0, 2);
+
+    assert_eq!(colors.len(), 2);
+    assert_eq!(colors[0].color, candidates[0].color);
+    assert_eq!(colors[1].color, candidates[1].color);
+    assert!((colors[0].weight - 0.64).abs() < 1e-9);
+    assert!((colors[1].weight - 0.36).abs() < 1e-9);
+    // No weight is lost by colors that don't fit.
+    assert!((weights(&colors).iter().sum::<f64>() - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn merged_colors_are_sorted_by_weight() {
+    let candidates = weighted_palette(&[
+        ((200, 40, 40), 0.3),
+        ((200, 40, 90), 0.25),
+        ((40, 40, 200), 0.25),
+        ((200, 40, 70), 0.2),
+    ]);
+
+    let colors = deduplicate_colors(candidates.clone(), 10.0, 10);
+
+    // The second color became the most dominant, and the colors of equal weight keep their order.
+    assert_eq!(
+        colors.iter().map(|color| color.color).collect::<Vec<_>>(),
+        vec![
+            candidates[1].color,
+            candidates[0].color,
+            candidates[2].color
+        ]
+    );
+    assert_eq!(weights(&colors), vec![0.45, 0.3, 0.25]);
+}
@@ -0,0 +1,140 @@
+use desktop_dye_api::{
+    config::{Downsampling, DownsamplingMethod},
+    functions::{convert_raw_pixels, downsample_frame, RawPixelFormat, RawPixels},
+    models::{
+        colors::RgbColor,
+        frame::{Frame, Rect},
+    },
+};
+
+fn downsampling(method: DownsamplingMethod, max_pixels: u32) -> Downsampling {
+    Downsampling { method, max_pixels }
+}
+
+/// A frame in which the value of every channel of a pixel is its index.
+fn numbered_frame(width: u32, height: u32) -> Frame {
+    let pixels = (0..width * height)
+        .map(|index| RgbColor::new(index as u8, index as u8, index as u8))
+        .collect();
+
+    Frame::new(width, height, pixels)
+}
+
+fn values(frame: &Frame) -> Vec<u8> {
+    frame.pixels.iter().map(|pixel| pixel.red()).collect()
+}
+
+#[test]
+fn frames_are_shrunk_to_fit_within_max_pixels() {
+    for (width, height, max_pixels) in [(1920, 1080, 50000), (100, 37, 500), (7, 3, 1)] {
+        let factor = downsampling(DownsamplingMethod::Box, max_pixels).factor(width, height);
+
+        assert!(width.div_ceil(factor) * height.div_ceil(factor) <= max_pixels);
+        assert!(
+            factor == 1 || width.div_ceil(factor - 1) * height.div_ceil(factor - 1) > max_pixels,
+            "factor {} is larger than needed for {}x{} within {} pixels",
+            factor,
+            width,
+            height,
+            max_pixels
+        );
+    }
+}
+
+#[test]
+fn frames_within_max_pixels_are_kept() {
+    let frame = numbered_frame(4, 4);
+
+    for method in [DownsamplingMethod::Box, DownsamplingMethod::Stride] {
+        assert_eq!(downsample_frame(&frame, &downsampling(method, 16)), frame);
+    }
+}
+
+#[test]
+fn box_averages_blocks_and_stride_keeps_their_first_pixel() {
+    let frame = numbered_frame(4, 4);
+
+    let boxed = downsample_frame(&frame, &downsampling(DownsamplingMethod::Box, 4));
+    let strided = downsample_frame(&frame, &downsampling(DownsamplingMethod::Stride, 4));
+
+    assert_eq!((boxed.width, boxed.height), (2, 2));
+    assert_eq!(values(&boxed), vec![2, 4, 10, 12]);
+    assert_eq!((strided.width, strided.height), (2, 2));
+    assert_eq!(values(&strided), vec![0, 2, 8, 10]);
+}
+
+#[test]
+fn blocks_at_the_edges_of_frames_that_are_not_a_multiple_of_the_factor_are_smaller() {
+    let frame = numbered_frame(5, 3);
+
+    let boxed = downsample_frame(&frame, &downsampling(DownsamplingMethod::Box, 6));
+    let strided = downsample_frame(&frame, &downsampling(DownsamplingMethod::Stride, 6));
+
+    assert_eq!((boxed.width, boxed.height), (3, 2));
+    // The last column and row of blocks are 1 pixel wide and tall.
+    assert_eq!(values(&boxed), vec![3, 5, 6, 10, 12, 14]);
+    assert_eq!(values(&strided), vec![0, 2, 4, 10, 12, 14]);
+}
+
+#[test]
+fn box_skips_excluded_pixels_and_excludes_fully_excluded_blocks() {
+    let mut frame = numbered_frame(4, 2);
+    frame.exclude_rect(&Rect::new(0, 0, 1, 2));
+    frame.exclude_rect(&Rect::new(2, 0, 2, 2));
+
+    let boxed = downsample_frame(&frame, &downsampling(DownsamplingMethod::Box, 2));
+
+    assert_eq!(values(&boxed)[0], 3);
+    assert!(!boxed.is_excluded(0));
+    assert!(boxed.is_excluded(1));
+    assert_eq!(boxed.included_pixels().len(), 1);
+}
+
+#[test]
+fn blocks_larger_than_4096_by_4096_pixels_are_averaged() {
+    let color = RgbColor::new(255, 128, 1);
+    let frame = Frame::new(4200, 4200, vec![color; 4200 * 4200]);
+
+    let downsampled = downsample_frame(&frame, &downsampling(DownsamplingMethod::Box, 1));
+
+    assert_eq!(
+        (downsampled.width, downsampled.height, downsampled.pixels),
+        (1, 1, vec![color])
+    );
+}
+
+#[test]
+fn raw_conversion_averages_like_box_downsampling() {
+    let (width, height) = (7, 5);
+    let frame = numbered_frame(width, height);
+    let bytes = frame
+        .pixels
+        .iter()
+        .flat_map(|pixel| [pixel.blue(), pixel.green() / 2, pixel.red() / 4, u8::MAX])
+        .collect::<Vec<_>>();
+    let raw = RawPixels {
+        bytes: &bytes,
+        width,
+        height,
+        stride: width as usize * 4,
+        format: RawPixelFormat::Bgra,
+    };
+
+    let converted = convert_raw_pixels(&raw, 3).unwrap();
+    let boxed = downsample_frame(
+        &Frame::new(
+            width,
+            height,
+            frame
+                .pixels
+                .iter()
+                .map(|pixel| RgbColor::new(pixel.red() / 4, pixel.green() / 2, pixel.blue()))
+                .collect(),
+        ),
+        &downsampling(DownsamplingMethod::Box, 9),
+    );
+
+    assert_eq!(converted.scale, 3);
+    assert_eq!((converted.width, converted.height), (3, 2));
+    assert_eq!(converted.pixels, boxed.pixels);
+}
@@ -0,0 +1,108 @@
+use desktop_dye_api::{
+    config::{DesktopDyeConfig, EdgeZones, HueHistogramSettings, KMeansSettings},
+    functions::{calculate_edge_zone_colors, PaletteExtractorRegistry},
+    models::{
+        colors::RgbColor,
+        frame::{Frame, Rect},
+    },
+};
+
+fn edge_zones(yaml: &str) -> EdgeZones {
+    serde_yaml::from_str(yaml).unwrap()
+}
+
+#[test]
+fn zones_are_ordered_clockwise_from_the_top_left_corner() {
+    let zones = edge_zones("{ depth: 2, top: 2, right: 1, bottom: 2, left: 1 }");
+
+    assert_eq!(zones.count(), 6);
+    assert_eq!(
+        zones.resolve(10, 8),
+        vec![
+            Rect::new(0, 0, 5, 2),
+            Rect::new(5, 0, 5, 2),
+            Rect::new(8, 0, 2, 8),
+            Rect::new(5, 6, 5, 2),
+            Rect::new(0, 6, 5, 2),
+            Rect::new(0, 0, 2, 8),
+        ]
+    );
+}
+
+#[test]
+fn the_last_segments_of_a_side_get_the_remainder() {
+    let zones = edge_zones("{ depth: 2, top: 3, right: 0, bottom: 0, left: 3 }");
+
+    assert_eq!(
+        zones.resolve(10, 8),
+        vec![
+            Rect::new(0, 0, 3, 2),
+            Rect::new(3, 0, 3, 2),
+            Rect::new(6, 0, 4, 2),
+            // The left side runs from bottom to top.
+            Rect::new(0, 5, 2, 3),
+            Rect::new(0, 2, 2, 3),
+            Rect::new(0, 0, 2, 2),
+        ]
+    );
+}
+
+#[test]
+fn empty_frames_have_empty_zones() {
+    let zones = edge_zones("{ depth: 2, top: 2, right: 1, bottom: 1, left: 1 }");
+
+    for (width, height) in [(0, 8), (10, 0), (0, 0)] {
+        let rects = zones.resolve(width, height);
+
+        assert_eq!(rects.len(), 5);
+        assert!(rects.iter().all(|rect| rect.width == 0 || rect.height == 0));
+    }
+}
+
+#[test]
+fn every_zone_gets_the_color_of_its_own_edge() {
+    let config = DesktopDyeConfig::from_yaml(
+        "
+ha_endpoint: http://localhost:8123
+ha_token: token
+ha_target_entity_id: light.test
+algorithm: hue_histogram
+edge_zones:
+  depth: 2
+",
+    )
+    .unwrap();
+    let registry = PaletteExtractorRegistry::with_builtin_extractors(
+        &KMeansSettings::default(),
+        &HueHistogramSettings::default(),
+    );
+    let (red, yellow, blue, green) = (
+        RgbColor::new(200, 0, 0),
+        RgbColor::new(200, 200, 0),
+        RgbColor::new(0, 0, 200),
+        RgbColor::new(0, 200, 0),
+    );
+    // Every edge is 2 pixels deep and has its own color, the left and right edges covering the
+    // corners.
+    let pixels = (0..20)
+        .flat_map(|y| (0..20).map(move |x| (x, y)))
+        .map(|(x, y)| match (x, y) {
+            (0..=1, _) => green,
+            (18.., _) => yellow,
+            (_, 0..=1) => red,
+            (_, 18..) => blue,
+            _ => RgbColor::new(128, 128, 128),
+        })
+        .collect();
+    let frame = Frame::new(20, 20, pixels);
+
+    let colors = calculate_edge_zone_colors(
+        &frame,
+        config.edge_zones.as_ref().unwrap(),
+        &config,
+        &registry,
+    )
+    .unwrap();
+
+    assert_eq!(colors, vec![red, yellow, blue, green]);
+}
@@ -0,0 +1,107 @@
+mod common;
+
+use std::path::PathBuf;
+
+use desktop_dye_api::{
+    config::{DesktopDyeConfig, ExclusionMask},
+    functions::capture_frame,
+    models::{colors::RgbColor, frame::Frame},
+};
+use png::{BitDepth, ColorType};
+
+use common::{encode_png, StaticPixelSource};
+
+/// Writes a 4x1 PNG image with the given color type and bit depth to a temporary file, and
+/// loads it as an exclusion mask.
+fn load_mask(name: &str, color_type: ColorType, bit_depth: BitDepth, data: &[u8]) -> ExclusionMask {
+    let path = std::env::temp_dir().join(format!(
+        "desktop_dye_mask_{}_{}.png",
+        std::process::id(),
+        name
+    ));
+
+    std::fs::write(&path, encode_png(4, 1, color_type, bit_depth, data, None)).unwrap();
+
+    let mask = ExclusionMask::try_from(PathBuf::from(&path));
+    std::fs::remove_file(&path).unwrap();
+
+    mask.unwrap()
+}
+
+fn excluded_pixels(mask: &ExclusionMask, width: u32) -> Vec<bool> {
+    let mut frame = Frame::new(width, 1, vec![RgbColor::new(255, 255, 255); width as usize]);
+    mask.apply(&mut frame);
+
+    (0..width as usize)
+        .map(|index| frame.is_excluded(index))
+        .collect()
+}
+
+#[test]
+fn exclusion_regions_overhanging_the_frame_are_clamped() {
+    let config = DesktopDyeConfig::from_yaml(
+        "
+ha_endpoint: http://localhost:8123
+ha_token: token
+ha_target_entity_id: light.test
+exclusion_regions:
+  - { x: 2, y: 0, width: 10, height: 5 }
+",
+    )
+    .unwrap();
+    let mut source = StaticPixelSource(Frame::new(4, 2, vec![RgbColor::new(255, 255, 255); 8]));
+
+    let frame = capture_frame(&config, &mut source).unwrap();
+
+    assert_eq!(
+        (0..8)
+            .map(|index| frame.is_excluded(index))
+            .collect::<Vec<_>>(),
+        vec![false, false, true, true, false, false, true, true]
+    );
+}
+
+#[test]
+fn grayscale_masks_exclude_dark_pixels() {
+    let mask = load_mask(
+        "gray",
+        ColorType::Grayscale,
+        BitDepth::Eight,
+        &[0, 100, 150, 255],
+    );
+
+    assert_eq!(excluded_pixels(&mask, 4), vec![true, true, false, false]);
+}
+
+#[test]
+fn sixteen_bit_grayscale_masks_are_supported() {
+    let data = [[0x00, 0x00], [0x7f, 0xff], [0x80, 0x00], [0xff, 0xff]].concat();
+    let mask = load_mask("gray16", ColorType::Grayscale, BitDepth::Sixteen, &data);
+
+    assert_eq!(excluded_pixels(&mask, 4), vec![true, true, false, false]);
+}
+
+#[test]
+fn color_masks_are_reduced_to_their_gray_level() {
+    // Pure blue is dark, even though one of its channels is at its maximum.
+    let data = [[0, 0, 255], [0, 255, 0], [255, 0, 0], [200, 200, 200]].concat();
+    let mask = load_mask("rgb", ColorType::Rgb, BitDepth::Eight, &data);
+
+    assert_eq!(excluded_pixels(&mask, 4), vec![true, false, true, false]);
+}
+
+#[test]
+fn transparent_mask_pixels_exclude_and_masks_are_stretched() {
+    let data = [[255, 255], [255, 0], [0, 255], [255, 255]].concat();
+    let mask = load_mask(
+        "gray_alpha",
+        ColorType::GrayscaleAlpha,
+        BitDepth::Eight,
+        &data,
+    );
+
+    assert_eq!(
+        excluded_pixels(&mask, 8),
+        vec![false, false, true, true, true, true, false, false]
+    );
+}
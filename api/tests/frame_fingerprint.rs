@@ -0,0 +1,69 @@
+use desktop_dye_api::{
+    config::DEFAULT_FRAME_CHANGE_THRESHOLD,
+    functions::FrameFingerprint,
+    models::{colors::RgbColor, frame::Frame},
+};
+
+fn gray_frame(width: u32, height: u32) -> Frame {
+    Frame::new(
+        width,
+        height,
+        vec![RgbColor::new(128, 128, 128); (width * height) as usize],
+    )
+}
+
+#[test]
+fn identical_frames_do_not_differ() {
+    let frame = gray_frame(160, 90);
+
+    let fingerprint = FrameFingerprint::new(&frame);
+
+    assert_eq!(fingerprint.difference(&FrameFingerprint::new(&frame)), 0.0);
+}
+
+#[test]
+fn frames_of_different_sizes_are_completely_different() {
+    let fingerprint = FrameFingerprint::new(&gray_frame(160, 90));
+
+    assert_eq!(
+        fingerprint.difference(&FrameFingerprint::new(&gray_frame(90, 160))),
+        1.0
+    );
+}
+
+#[test]
+fn small_local_changes_stay_below_the_default_threshold() {
+    let frame = gray_frame(160, 90);
+    let mut changed_frame = frame.clone();
+    for y in 40..50 {
+        for x in 70..80 {
+            changed_frame.pixels[(y * 160 + x) as usize] = RgbColor::new(255, 255, 255);
+        }
+    }
+
+    let difference =
+        FrameFingerprint::new(&frame).difference(&FrameFingerprint::new(&changed_frame));
+
+    assert!(difference > 0.0);
+    assert!(
+        difference < DEFAULT_FRAME_CHANGE_THRESHOLD,
+        "{}",
+        difference
+    );
+}
+
+#[test]
+fn large_changes_exceed_the_default_threshold() {
+    let frame = gray_frame(160, 90);
+    let mut changed_frame = frame.clone();
+    changed_frame.pixels[..160 * 45].fill(RgbColor::new(255, 255, 255));
+
+    let difference =
+        FrameFingerprint::new(&frame).difference(&FrameFingerprint::new(&changed_frame));
+
+    assert!(
+        difference > DEFAULT_FRAME_CHANGE_THRESHOLD,
+        "{}",
+        difference
+    );
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use desktop_dye_api::{
+    config::{GradientColorSpace, GradientSettings},
+    functions::{interpolate_gradient, order_by_hue, ToRgb},
+    models::colors::HsvColor,
+};
+
+use common::palette;
+
+fn rgb(color: &HsvColor) -> (u8, u8, u8) {
+    let rgb = color.to_rgb();
+    (rgb.red(), rgb.green(), rgb.blue())
+}
+
+#[test]
+fn colors_are_ordered_around_the_largest_hue_gap() {
+    // Blue, red, gray and green: the largest gap is between blue and red (through magenta), so
+    // the gradient runs from red through green to blue, followed by gray.
+    let colors = palette(&[(0, 0, 255), (255, 0, 0), (128, 128, 128), (0, 255, 0)])
+        .into_iter()
+        .map(|dominant_color| dominant_color.color)
+        .collect::<Vec<_>>();
+
+    let ordered = order_by_hue(&colors).iter().map(rgb).collect::<Vec<_>>();
+
+    assert_eq!(
+        ordered,
+        vec![(255, 0, 0), (0, 255, 0), (0, 0, 255), (128, 128, 128)]
+    );
+}
+
+#[test]
+fn gradient_has_the_configured_count_and_passes_through_the_dominant_colors() {
+    let colors = palette(&[(255, 0, 0), (0, 0, 255), (0, 255, 0)]);
+
+    for color_space in [
+        GradientColorSpace::Oklab,
+        GradientColorSpace::Oklch,
+        GradientColorSpace::Rgb,
+    ] {
+        let gradient = interpolate_gradient(
+            &colors,
+            &GradientSettings {
+                count: 5,
+                color_space,
+            },
+        );
+
+        assert_eq!(gradient.len(), 5, "{}", color_space);
+        assert_eq!(rgb(&gradient[0].color), (255, 0, 0), "{}", color_space);
+        assert_eq!(rgb(&gradient[2].color), (0, 255, 0), "{}", color_space);
+        assert_eq!(rgb(&gradient[4].color), (0, 0, 255), "{}", color_space);
+        for dominant_color in &gradient {
+            assert_eq!(dominant_color.weight, 0.2);
+        }
+    }
+}
+
+#[test]
+fn gradient_interpolates_in_the_configured_color_space() {
+    let colors = palette(&[(0, 0, 0), (255, 255, 255)]);
+    let middle = |color_space| {
+        let gradient = interpolate_gradient(
+            &colors,
+            &GradientSettings {
+                count: 3,
+                color_space,
+            },
+        );
+
+        rgb(&gradient[1].color)
+    };
+
+    // Halfway between black and white in OKLab is a perceptual middle gray, which has lower
+    // channels than the gray halfway between their channels.
+    assert_eq!(middle(GradientColorSpace::Rgb), (127, 127, 127));
+    assert_eq!(middle(GradientColorSpace::Oklab), (99, 99, 99));
+    assert_eq!(middle(GradientColorSpace::Oklch), (99, 99, 99));
+}
+
+#[test]
+fn single_dominant_color_fills_the_gradient() {
+    let colors = palette(&[(30, 60, 90)]);
+    let gradient = interpolate_gradient(&colors, &GradientSettings::default());
+
+    assert_eq!(gradient.len(), GradientSettings::default().count as usize);
+    for dominant_color in &gradient {
+        assert_eq!(rgb(&dominant_color.color), (30, 60, 90));
+    }
+}
@@ -0,0 +1,50 @@
+use desktop_dye_api::{
+    config::HueHistogramSettings, functions::hue_histogram, models::colors::RgbColor,
+};
+
+/// Returns the given amount of pixels of every given RGB color.
+fn pixels(colors: &[((u8, u8, u8), usize)]) -> Vec<RgbColor> {
+    colors
+        .iter()
+        .flat_map(|((red, green, blue), count)| vec![RgbColor::new(*red, *green, *blue); *count])
+        .collect()
+}
+
+#[test]
+fn small_vivid_areas_outrank_large_dull_areas() {
+    // The dull color is just saturated enough not to be ignored.
+    let pixels = pixels(&[((112, 128, 140), 900), ((255, 0, 0), 120)]);
+
+    let colors = hue_histogram(&pixels, 3, &HueHistogramSettings::default());
+
+    assert_eq!(colors.len(), 2);
+    assert_eq!(colors[0].0, RgbColor::new(255, 0, 0));
+    assert_eq!(colors[1].0, RgbColor::new(112, 128, 140));
+    assert!(colors[0].1 > colors[1].1);
+    assert!((colors.iter().map(|(_, weight)| weight).sum::<f64>() - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn achromatic_and_dark_pixels_are_ignored() {
+    let pixels = pixels(&[
+        ((128, 128, 128), 500),
+        // Below the minimum saturation.
+        ((120, 128, 136), 500),
+        // Below the minimum brightness.
+        ((30, 0, 0), 500),
+        ((0, 0, 200), 10),
+    ]);
+
+    let colors = hue_histogram(&pixels, 3, &HueHistogramSettings::default());
+
+    assert_eq!(colors, vec![(RgbColor::new(0, 0, 200), 1.0)]);
+}
+
+#[test]
+fn the_average_color_is_used_when_every_pixel_is_ignored() {
+    let pixels = pixels(&[((100, 100, 100), 3), ((200, 200, 200), 1)]);
+
+    let colors = hue_histogram(&pixels, 3, &HueHistogramSettings::default());
+
+    assert_eq!(colors, vec![(RgbColor::new(125, 125, 125), 1.0)]);
+}
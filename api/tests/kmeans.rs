@@ -0,0 +1,94 @@
+use desktop_dye_api::{config::KMeansSettings, functions::kmeans_lab, models::colors::RgbColor};
+
+fn settings(seed: u64) -> KMeansSettings {
+    KMeansSettings {
+        seed,
+        ..KMeansSettings::default()
+    }
+}
+
+/// Pixels spread evenly over the RGB cube, so there are no obvious clusters and the result
+/// depends on the initial centers.
+fn spread_pixels() -> Vec<RgbColor> {
+    let mut pixels = Vec::new();
+    for red in (0..=255).step_by(51) {
+        for green in (0..=255).step_by(51) {
+            for blue in (0..=255).step_by(51) {
+                pixels.push(RgbColor::new(red, green, blue));
+            }
+        }
+    }
+
+    pixels
+}
+
+/// A fixed frame with a large dark blue area, a smaller orange area and a few gray pixels.
+fn snapshot_pixels() -> Vec<RgbColor> {
+    let mut pixels = Vec::new();
+    for index in 0..60u8 {
+        pixels.push(RgbColor::new(
+            10 + index % 5,
+            20 + index % 7,
+            90 + index % 11,
+        ));
+    }
+    for index in 0..30u8 {
+        pixels.push(RgbColor::new(
+            230 + index % 9,
+            120 + index % 13,
+            20 + index % 3,
+        ));
+    }
+    for index in 0..10u8 {
+        pixels.push(RgbColor::new(120 + index, 120 + index, 120 + index));
+    }
+
+    pixels
+}
+
+#[test]
+fn same_pixels_and_seed_give_the_same_colors() {
+    let pixels = spread_pixels();
+    let mut reversed = pixels.clone();
+    reversed.reverse();
+
+    for seed in [0, 1, 42] {
+        let colors = kmeans_lab(&pixels, 5, &settings(seed));
+
+        assert_eq!(kmeans_lab(&pixels, 5, &settings(seed)), colors);
+        assert_eq!(kmeans_lab(&reversed, 5, &settings(seed)), colors);
+    }
+}
+
+#[test]
+fn different_seeds_can_give_different_colors() {
+    let pixels = spread_pixels();
+    let colors = kmeans_lab(&pixels, 5, &settings(0));
+
+    assert!((1..10).any(|seed| kmeans_lab(&pixels, 5, &settings(seed)) != colors));
+}
+
+#[test]
+fn fixed_pixels_give_the_snapshotted_colors() {
+    let colors = kmeans_lab(&snapshot_pixels(), 3, &settings(0));
+    assert_eq!(
+        colors,
+        vec![
+            (RgbColor::new(12, 23, 95), 60),
+            (RgbColor::new(234, 125, 21), 30),
+            (RgbColor::new(124, 124, 124), 10),
+        ]
+    );
+
+    // Without obvious clusters, this also depends on the initial centers and every iteration.
+    let colors = kmeans_lab(&spread_pixels(), 4, &settings(0));
+    assert_eq!(
+        colors,
+        vec![
+            (RgbColor::new(136, 215, 88), 65),
+            (RgbColor::new(143, 57, 202), 55),
+            (RgbColor::new(110, 146, 162), 53),
+            (RgbColor::new(203, 84, 67), 43),
+        ]
+    );
+}
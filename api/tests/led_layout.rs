@@ -0,0 +1,173 @@
+use desktop_dye_api::{
+    config::LedLayout,
+    functions::calculate_led_colors,
+    models::{
+        colors::RgbColor,
+        frame::{Frame, Rect},
+    },
+};
+
+fn layout(yaml: &str) -> LedLayout {
+    serde_yaml::from_str(yaml).unwrap()
+}
+
+/// The sampling boxes of a layout with 2 LEDs at the top and bottom and 1 at the sides, for an
+/// 8x6 frame with a depth of 2 pixels, numbered clockwise from the top left corner.
+fn positions() -> [Rect; 6] {
+    [
+        Rect::new(0, 0, 4, 2),
+        Rect::new(4, 0, 4, 2),
+        Rect::new(6, 0, 2, 6),
+        Rect::new(4, 4, 4, 2),
+        Rect::new(0, 4, 4, 2),
+        Rect::new(0, 0, 2, 6),
+    ]
+}
+
+/// Returns the numbers of the positions of the LEDs of the given layout settings on the strip.
+fn resolved_positions(settings: &str) -> Vec<usize> {
+    layout(&format!(
+        "{{ top: 2, right: 1, bottom: 2, left: 1, depth: 2, {} }}",
+        settings
+    ))
+    .resolve(8, 6)
+    .iter()
+    .map(|rect| {
+        positions()
+            .iter()
+            .position(|position| position == rect)
+            .unwrap()
+    })
+    .collect()
+}
+
+#[test]
+fn positions_within_gaps_have_no_led() {
+    let layout =
+        layout("{ top: 4, right: 2, bottom: 4, left: 2, gaps: [{ start: 2, length: 3 }] }");
+
+    assert!(layout.validate().is_empty());
+    assert_eq!(layout.position_count(), Some(12));
+    assert_eq!(layout.led_count(), 9);
+}
+
+#[test]
+fn gaps_must_end_within_the_positions() {
+    let layout =
+        layout("{ top: 4, right: 2, bottom: 4, left: 2, gaps: [{ start: 10, length: 3 }] }");
+
+    assert_eq!(layout.validate().len(), 1);
+}
+
+#[test]
+fn gaps_that_overflow_are_rejected() {
+    let layout = layout(&format!(
+        "{{ top: 4, right: 2, bottom: 4, left: 2, gaps: [{{ start: 2, length: {} }}] }}",
+        u32::MAX
+    ));
+
+    let errors = layout.validate();
+
+    assert!(
+        errors.iter().any(|error| error.starts_with("LED gap 1")),
+        "{:?}",
+        errors
+    );
+    // The gap covers every position from its start, so only the first two LEDs remain.
+    assert_eq!(layout.led_count(), 2);
+}
+
+#[test]
+fn position_counts_that_overflow_are_rejected() {
+    let layout = layout(&format!("{{ top: {}, right: 1 }}", u32::MAX));
+
+    assert_eq!(layout.position_count(), None);
+    assert_eq!(layout.validate().len(), 1);
+    assert_eq!(layout.led_count(), 0);
+}
+
+#[test]
+fn clockwise_strips_start_at_their_start_corner() {
+    for (start_corner, expected) in [
+        ("top_left", vec![0, 1, 2, 3, 4, 5]),
+        ("top_right", vec![2, 3, 4, 5, 0, 1]),
+        ("bottom_right", vec![3, 4, 5, 0, 1, 2]),
+        ("bottom_left", vec![5, 0, 1, 2, 3, 4]),
+    ] {
+        assert_eq!(
+            resolved_positions(&format!("start_corner: {}", start_corner)),
+            expected,
+            "{}",
+            start_corner
+        );
+    }
+
+    // Strips start at the bottom left corner and run clockwise by default.
+    assert_eq!(resolved_positions(""), vec![5, 0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn counter_clockwise_strips_start_at_their_start_corner() {
+    for (start_corner, expected) in [
+        ("top_left", vec![5, 4, 3, 2, 1, 0]),
+        ("top_right", vec![1, 0, 5, 4, 3, 2]),
+        ("bottom_right", vec![2, 1, 0, 5, 4, 3]),
+        ("bottom_left", vec![4, 3, 2, 1, 0, 5]),
+    ] {
+        assert_eq!(
+            resolved_positions(&format!(
+                "start_corner: {}, direction: counter_clockwise",
+                start_corner
+            )),
+            expected,
+            "{}",
+            start_corner
+        );
+    }
+}
+
+#[test]
+fn offsets_move_the_start_along_the_strip_and_wrap_around() {
+    let expected = vec![2, 3, 4, 5, 0, 1];
+    assert_eq!(
+        resolved_positions("start_corner: top_left, offset: 2"),
+        expected
+    );
+    assert_eq!(
+        resolved_positions("start_corner: top_left, offset: 8"),
+        expected
+    );
+
+    assert_eq!(
+        resolved_positions("start_corner: top_left, direction: counter_clockwise, offset: 7"),
+        vec![4, 3, 2, 1, 0, 5]
+    );
+}
+
+#[test]
+fn every_led_averages_the_included_pixels_of_its_box() {
+    let (width, height) = (8, 6);
+    // The red and green channels of every pixel are 10 times its x and y coordinate.
+    let pixels = (0..height)
+        .flat_map(|y| (0..width).map(move |x| RgbColor::new(x as u8 * 10, y as u8 * 10, 0)))
+        .collect();
+    let mut frame = Frame::new(width, height, pixels);
+    frame.exclude_rect(&Rect::new(0, 0, 1, height));
+    let layout =
+        layout("{ top: 2, right: 1, bottom: 2, left: 1, depth: 2, start_corner: top_left }");
+
+    let colors = calculate_led_colors(&frame, &layout);
+
+    // The excluded left column is left out of the boxes that contain it.
+    assert_eq!(
+        colors,
+        vec![
+            RgbColor::new(20, 5, 0),
+            RgbColor::new(55, 5, 0),
+            RgbColor::new(65, 25, 0),
+            RgbColor::new(55, 45, 0),
+            RgbColor::new(20, 45, 0),
+            RgbColor::new(10, 25, 0),
+        ]
+    );
+}
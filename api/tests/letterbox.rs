@@ -0,0 +1,104 @@
+use desktop_dye_api::{
+    functions::{detect_picture_area, LetterboxDetector, DEFAULT_LETTERBOX_STABLE_CAPTURES},
+    models::{
+        colors::RgbColor,
+        frame::{Frame, Rect},
+    },
+};
+
+const WIDTH: u32 = 100;
+const HEIGHT: u32 = 60;
+
+/// A frame with a gray picture in the given area and black bars around it.
+fn boxed_frame(picture_area: Rect) -> Frame {
+    let pixels = (0..HEIGHT)
+        .flat_map(|y| (0..WIDTH).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            let in_picture = x >= picture_area.x
+                && x < picture_area.x + picture_area.width
+                && y >= picture_area.y
+                && y < picture_area.y + picture_area.height;
+            if in_picture {
+                RgbColor::new(128, 128, 128)
+            } else {
+                RgbColor::new(0, 0, 0)
+            }
+        })
+        .collect();
+
+    Frame::new(WIDTH, HEIGHT, pixels)
+}
+
+fn full_frame() -> Frame {
+    boxed_frame(Rect::new(0, 0, WIDTH, HEIGHT))
+}
+
+/// Updates the detector with the given frame until just before the picture area would be used.
+fn update_until_stable(detector: &mut LetterboxDetector, frame: &Frame) {
+    for _ in 1..DEFAULT_LETTERBOX_STABLE_CAPTURES {
+        assert_eq!(detector.update(frame), None);
+    }
+}
+
+#[test]
+fn letterbox_bars_are_cropped_once_stable() {
+    let picture_area = Rect::new(0, 10, WIDTH, 40);
+    let frame = boxed_frame(picture_area);
+    let mut detector = LetterboxDetector::default();
+
+    assert_eq!(detect_picture_area(&frame), Some(picture_area));
+    update_until_stable(&mut detector, &frame);
+    assert_eq!(detector.update(&frame), Some(picture_area));
+    assert_eq!(detector.picture_area(), Some(picture_area));
+}
+
+#[test]
+fn pillarbox_bars_are_cropped_once_stable() {
+    let picture_area = Rect::new(15, 0, 70, HEIGHT);
+    let frame = boxed_frame(picture_area);
+    let mut detector = LetterboxDetector::default();
+
+    assert_eq!(detect_picture_area(&frame), Some(picture_area));
+    update_until_stable(&mut detector, &frame);
+    assert_eq!(detector.update(&frame), Some(picture_area));
+}
+
+#[test]
+fn flickering_bars_do_not_change_the_crop() {
+    let letterboxed = boxed_frame(Rect::new(0, 10, WIDTH, 40));
+    let pillarboxed = boxed_frame(Rect::new(15, 0, 70, HEIGHT));
+
+    // Bars that appear in every other capture are never used.
+    let mut detector = LetterboxDetector::default();
+    for _ in 0..DEFAULT_LETTERBOX_STABLE_CAPTURES * 2 {
+        assert_eq!(detector.update(&letterboxed), None);
+        assert_eq!(detector.update(&full_frame()), None);
+    }
+
+    // Once bars are used, a different picture area in every other capture doesn't replace them.
+    update_until_stable(&mut detector, &letterboxed);
+    let picture_area = detector.update(&letterboxed);
+    assert!(picture_area.is_some());
+    for _ in 0..DEFAULT_LETTERBOX_STABLE_CAPTURES * 2 {
+        assert_eq!(detector.update(&full_frame()), picture_area);
+        assert_eq!(detector.update(&pillarboxed), picture_area);
+        assert_eq!(detector.update(&letterboxed), picture_area);
+    }
+}
+
+#[test]
+fn dark_frames_keep_the_current_crop() {
+    let letterboxed = boxed_frame(Rect::new(0, 10, WIDTH, 40));
+    let dark = Frame::new(
+        WIDTH,
+        HEIGHT,
+        vec![RgbColor::new(0, 0, 0); (WIDTH * HEIGHT) as usize],
+    );
+    let mut detector = LetterboxDetector::default();
+
+    update_until_stable(&mut detector, &letterboxed);
+    let picture_area = detector.update(&letterboxed);
+    for _ in 0..DEFAULT_LETTERBOX_STABLE_CAPTURES * 2 {
+        assert_eq!(detector.update(&dark), picture_area);
+    }
+}
@@ -0,0 +1,166 @@
+use desktop_dye_api::{
+    config::{DominantColorAlgorithm, HueHistogramSettings, KMeansSettings},
+    functions::{
+        calculate_dominant_colors, check_palette_extractor, PaletteExtractor,
+        PaletteExtractorRegistry,
+    },
+    models::colors::{DominantColor, RgbColor},
+};
+
+fn builtin_registry() -> PaletteExtractorRegistry {
+    PaletteExtractorRegistry::with_builtin_extractors(
+        &KMeansSettings::default(),
+        &HueHistogramSettings::default(),
+    )
+}
+
+/// Returns the average color of the given pixels.
+struct AverageExtractor;
+
+impl PaletteExtractor for AverageExtractor {
+    fn name(&self) -> &str {
+        "average"
+    }
+
+    fn extract(&self, pixels: &[RgbColor], sample_size: u8) -> Vec<DominantColor> {
+        if pixels.is_empty() || sample_size == 0 {
+            return vec![];
+        }
+
+        let sum = |channel: fn(&RgbColor) -> u8| {
+            pixels
+                .iter()
+                .map(|pixel| channel(pixel) as usize)
+                .sum::<usize>()
+                / pixels.len()
+        };
+
+        vec![DominantColor::new(
+            RgbColor::new(
+                sum(RgbColor::red) as u8,
+                sum(RgbColor::green) as u8,
+                sum(RgbColor::blue) as u8,
+            ),
+            1.0,
+        )]
+    }
+}
+
+#[test]
+fn builtin_extractors_conform() {
+    let registry = builtin_registry();
+
+    let errors = registry
+        .names()
+        .into_iter()
+        .flat_map(|name| check_palette_extractor(registry.get(name).unwrap()))
+        .collect::<Vec<_>>();
+
+    assert!(errors.is_empty(), "{:#?}", errors);
+}
+
+#[test]
+fn every_algorithm_has_a_builtin_extractor() {
+    let registry = builtin_registry();
+
+    for algorithm in [
+        DominantColorAlgorithm::ColorThief,
+        DominantColorAlgorithm::Pigmnts,
+        DominantColorAlgorithm::KMeansLab,
+        DominantColorAlgorithm::Octree,
+        DominantColorAlgorithm::MedianCut,
+        DominantColorAlgorithm::HueHistogram,
+    ] {
+        assert!(
+            registry.get(algorithm.name()).is_some(),
+            "no extractor for {}",
+            algorithm.name()
+        );
+    }
+}
+
+#[test]
+fn custom_extractors_can_be_registered() {
+    let mut registry = builtin_registry();
+    registry.register(AverageExtractor);
+
+    assert!(check_palette_extractor(&AverageExtractor).is_empty());
+    assert_eq!(registry.names().last(), Some(&"average"));
+
+    let pixels = [RgbColor::new(0, 0, 0), RgbColor::new(100, 200, 50)];
+    let colors = registry.extract("average", &pixels, 3).unwrap();
+    assert_eq!(
+        colors,
+        vec![DominantColor::new(RgbColor::new(50, 100, 25), 1.0)]
+    );
+}
+
+#[test]
+fn registering_an_existing_name_replaces_the_extractor() {
+    struct FixedOctree;
+
+    impl PaletteExtractor for FixedOctree {
+        fn name(&self) -> &str {
+            "octree"
+        }
+
+        fn extract(&self, _: &[RgbColor], _: u8) -> Vec<DominantColor> {
+            vec![DominantColor::new(RgbColor::new(1, 2, 3), 1.0)]
+        }
+    }
+
+    let mut registry = builtin_registry();
+    let name_count = registry.names().len();
+    registry.register(FixedOctree);
+
+    assert_eq!(registry.names().len(), name_count);
+    assert_eq!(
+        registry
+            .extract("octree", &[RgbColor::new(200, 0, 0)], 1)
+            .unwrap()[0]
+            .color,
+        RgbColor::new(1, 2, 3)
+    );
+}
+
+#[test]
+fn unknown_names_are_an_error() {
+    assert!(builtin_registry().extract("unknown", &[], 1).is_err());
+}
+
+#[test]
+fn registered_extractors_can_be_selected_as_algorithm() {
+    let algorithm = serde_yaml::from_str::<DominantColorAlgorithm>("average").unwrap();
+    assert_eq!(
+        algorithm,
+        DominantColorAlgorithm::Registered("average".to_string())
+    );
+    assert_eq!(
+        serde_yaml::from_str::<DominantColorAlgorithm>("octree").unwrap(),
+        DominantColorAlgorithm::Octree
+    );
+
+    let pixels = [RgbColor::new(0, 0, 0), RgbColor::new(100, 200, 50)];
+    let mut registry = builtin_registry();
+    assert!(calculate_dominant_colors(&pixels, &registry, &algorithm, &3).is_err());
+
+    registry.register(AverageExtractor);
+    assert_eq!(
+        calculate_dominant_colors(&pixels, &registry, &algorithm, &3).unwrap(),
+        vec![DominantColor::new(RgbColor::new(50, 100, 25), 1.0)]
+    );
+}
+
+#[test]
+fn only_builtin_algorithms_are_valid_in_the_config() {
+    for algorithm in ["color_thief", "kmeans_lab", "hue_histogram"] {
+        let algorithm = serde_yaml::from_str::<DominantColorAlgorithm>(algorithm).unwrap();
+        assert!(algorithm.validate().is_empty(), "{}", algorithm);
+    }
+
+    let errors = serde_yaml::from_str::<DominantColorAlgorithm>("kmean_lab")
+        .unwrap()
+        .validate();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].ends_with("Found \"kmean_lab\""), "{}", errors[0]);
+}
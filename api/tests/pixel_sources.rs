@@ -0,0 +1,147 @@
+mod common;
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use desktop_dye_api::{
+    functions::{CombinedPixelSource, ImagePixelSource, PixelSource},
+    models::{colors::RgbColor, frame::Frame},
+};
+use png::{BitDepth, ColorType};
+
+use common::{encode_png, StaticPixelSource};
+
+/// Creates an empty temporary directory with the given name.
+fn temp_dir(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "desktop_dye_images_{}_{}",
+        std::process::id(),
+        name
+    ));
+    let _ = std::fs::remove_dir_all(&path);
+    std::fs::create_dir_all(&path).unwrap();
+
+    path
+}
+
+/// Writes a 1x1 PNG image of the given gray level to the given path.
+fn write_image(path: &Path, gray: u8) {
+    std::fs::write(
+        path,
+        encode_png(1, 1, ColorType::Grayscale, BitDepth::Eight, &[gray], None),
+    )
+    .unwrap();
+}
+
+/// Captures the given amount of frames and returns the gray level of each.
+fn captured_grays(source: &mut ImagePixelSource, count: usize) -> Vec<u8> {
+    (0..count)
+        .map(|_| source.capture().unwrap().pixels[0].red())
+        .collect()
+}
+
+/// A pixel source of a single color that downsamples while capturing, like a screen captured
+/// through X11.
+struct DownsamplingPixelSource {
+    width: u32,
+    height: u32,
+    color: RgbColor,
+}
+
+impl PixelSource for DownsamplingPixelSource {
+    fn description(&self) -> String {
+        "a downsampling source".to_string()
+    }
+
+    fn capture(&mut self) -> Result<Frame> {
+        self.capture_downsampled(&|_, _| 1)
+    }
+
+    fn capture_downsampled(
+        &mut self,
+        downsample_factor: &dyn Fn(u32, u32) -> u32,
+    ) -> Result<Frame> {
+        let factor = downsample_factor(self.width, self.height);
+        let (width, height) = (self.width.div_ceil(factor), self.height.div_ceil(factor));
+
+        let mut frame = Frame::new(width, height, vec![self.color; (width * height) as usize]);
+        frame.scale = factor;
+
+        Ok(frame)
+    }
+
+    fn size(&self) -> Option<(u32, u32)> {
+        Some((self.width, self.height))
+    }
+}
+
+#[test]
+fn combined_sources_are_downsampled_by_the_same_factor() {
+    let (red, blue) = (RgbColor::new(255, 0, 0), RgbColor::new(0, 0, 255));
+    let mut source = CombinedPixelSource::new(vec![
+        Box::new(StaticPixelSource(Frame::new(4, 4, vec![red; 16]))),
+        Box::new(DownsamplingPixelSource {
+            width: 4,
+            height: 2,
+            color: blue,
+        }),
+    ]);
+
+    let frame = source
+        .capture_downsampled(&|width, height| {
+            assert_eq!((width, height), (8, 4));
+            2
+        })
+        .unwrap();
+
+    assert_eq!((frame.width, frame.height, frame.scale), (4, 2, 2));
+    assert_eq!(frame.pixels[..4], [red, red, blue, blue]);
+    // The padding below the shorter source is excluded.
+    assert_eq!(
+        (0..8)
+            .map(|index| frame.is_excluded(index))
+            .collect::<Vec<_>>(),
+        vec![false, false, false, false, false, false, true, true]
+    );
+}
+
+#[test]
+fn single_images_are_captured_every_time() {
+    let dir = temp_dir("single");
+    let path = dir.join("image.png");
+    write_image(&path, 10);
+
+    let mut source = ImagePixelSource::new(&path).unwrap();
+
+    assert_eq!(source.description(), format!("image {}", path.display()));
+    assert_eq!(captured_grays(&mut source, 2), vec![10, 10]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn directories_are_cycled_through_in_file_name_order() {
+    let dir = temp_dir("directory");
+    write_image(&dir.join("b.png"), 20);
+    write_image(&dir.join("c.PNG"), 30);
+    write_image(&dir.join("a.png"), 10);
+    std::fs::write(dir.join("notes.txt"), "not an image").unwrap();
+
+    let mut source = ImagePixelSource::new(&dir).unwrap();
+
+    assert_eq!(source.description(), "3 images");
+    assert_eq!(captured_grays(&mut source, 4), vec![10, 20, 30, 10]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn empty_and_missing_directories_are_an_error() {
+    let dir = temp_dir("empty");
+    std::fs::write(dir.join("notes.txt"), "not an image").unwrap();
+
+    assert!(ImagePixelSource::new(&dir).is_err());
+    assert!(ImagePixelSource::new(&dir.join("missing")).is_err());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
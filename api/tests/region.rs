@@ -0,0 +1,106 @@
+use desktop_dye_api::config::{Dimension, Region};
+
+fn region(x: Dimension, y: Dimension, width: Dimension, height: Dimension) -> Region {
+    Region {
+        x,
+        y,
+        width,
+        height,
+    }
+}
+
+#[test]
+fn regions_are_resolved_in_pixels() {
+    let region = region(
+        Dimension::Pixels(100),
+        Dimension::Percentage(50.0),
+        Dimension::Percentage(25.0),
+        Dimension::Pixels(200),
+    );
+
+    let rect = region.resolve(1920, 1080).unwrap();
+
+    assert_eq!(
+        (rect.x, rect.y, rect.width, rect.height),
+        (100, 540, 480, 200)
+    );
+}
+
+#[test]
+fn regions_outside_of_the_frame_are_rejected() {
+    let region = region(
+        Dimension::Pixels(1800),
+        Dimension::Pixels(0),
+        Dimension::Pixels(200),
+        Dimension::Pixels(100),
+    );
+
+    assert!(region.resolve(1920, 1080).is_err());
+}
+
+#[test]
+fn regions_that_overflow_are_rejected() {
+    let overflowing_x = region(
+        Dimension::Pixels(u32::MAX),
+        Dimension::Pixels(0),
+        Dimension::Pixels(10),
+        Dimension::Pixels(10),
+    );
+    let overflowing_y = region(
+        Dimension::Pixels(0),
+        Dimension::Pixels(10),
+        Dimension::Pixels(10),
+        Dimension::Pixels(u32::MAX),
+    );
+
+    assert!(overflowing_x.resolve(1920, 1080).is_err());
+    assert!(overflowing_y.resolve(1920, 1080).is_err());
+}
+
+#[test]
+fn clamped_regions_are_cut_off_at_the_frame_edges() {
+    let region = region(
+        Dimension::Pixels(1800),
+        Dimension::Percentage(50.0),
+        Dimension::Pixels(200),
+        Dimension::Percentage(75.0),
+    );
+
+    let rect = region.resolve_clamped(1920, 1080);
+
+    assert_eq!(
+        (rect.x, rect.y, rect.width, rect.height),
+        (1800, 540, 120, 540)
+    );
+}
+
+#[test]
+fn percentage_regions_fit_frames_of_odd_size() {
+    let right_half = region(
+        Dimension::Percentage(50.0),
+        Dimension::Percentage(50.0),
+        Dimension::Percentage(50.0),
+        Dimension::Percentage(50.0),
+    );
+
+    let rect = right_half.resolve(1921, 1081).unwrap();
+
+    assert_eq!(
+        (rect.x, rect.y, rect.width, rect.height),
+        (961, 541, 960, 540)
+    );
+
+    let right_of_offset = region(
+        Dimension::Pixels(961),
+        Dimension::Pixels(0),
+        Dimension::Percentage(50.0),
+        Dimension::Percentage(100.0),
+    );
+
+    let rect = right_of_offset.resolve(1921, 1081).unwrap();
+
+    assert_eq!(
+        (rect.x, rect.y, rect.width, rect.height),
+        (961, 0, 960, 1081)
+    );
+}
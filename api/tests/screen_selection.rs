@@ -0,0 +1,141 @@
+use desktop_dye_api::{
+    config::{DesktopDyeConfig, ScreenSelection},
+    functions::assign_screen_targets,
+};
+use screenshots::{DisplayInfo, Screen};
+
+fn screen_selection(yaml: &str) -> Result<ScreenSelection, serde_yaml::Error> {
+    serde_yaml::from_str(yaml)
+}
+
+/// Returns a 100x100 screen with the given id, placed at the given horizontal position.
+fn screen(id: u32, x: i32, is_primary: bool) -> Screen {
+    Screen::new(&DisplayInfo {
+        id,
+        x,
+        y: 0,
+        width: 100,
+        height: 100,
+        rotation: 0.0,
+        scale_factor: 1.0,
+        is_primary,
+    })
+}
+
+fn screens() -> Vec<Screen> {
+    vec![
+        screen(1, 100, true),
+        screen(2, 200, false),
+        screen(3, 0, false),
+    ]
+}
+
+fn ids(screens: &[Screen]) -> Vec<u32> {
+    screens
+        .iter()
+        .map(|screen| screen.display_info.id)
+        .collect()
+}
+
+fn config(settings: &str) -> DesktopDyeConfig {
+    DesktopDyeConfig::from_yaml(&format!(
+        "
+ha_endpoint: http://localhost:8123
+ha_token: token
+ha_target_entity_id: light.main
+{}
+",
+        settings
+    ))
+    .unwrap()
+}
+
+#[test]
+fn screen_ids_are_parsed_as_an_id_a_list_or_all() {
+    assert_eq!(
+        screen_selection("2").unwrap(),
+        ScreenSelection::Ids(vec![2])
+    );
+    assert_eq!(
+        screen_selection("[1, 2]").unwrap(),
+        ScreenSelection::Ids(vec![1, 2])
+    );
+    assert_eq!(screen_selection("all").unwrap(), ScreenSelection::All);
+    assert!(screen_selection("primary").is_err());
+    assert!(!screen_selection("[]").unwrap().validate().is_empty());
+}
+
+#[test]
+fn screens_are_selected_from_left_to_right() {
+    let screens = screens();
+
+    assert_eq!(
+        ids(&ScreenSelection::select(None, &screens).unwrap()),
+        vec![1]
+    );
+    assert_eq!(
+        ids(&ScreenSelection::select(Some(&ScreenSelection::All), &screens).unwrap()),
+        vec![3, 1, 2]
+    );
+    assert_eq!(
+        ids(
+            &ScreenSelection::select(Some(&ScreenSelection::Ids(vec![2, 1, 2])), &screens).unwrap()
+        ),
+        vec![1, 2]
+    );
+}
+
+#[test]
+fn unknown_screen_ids_are_an_error() {
+    let selection = ScreenSelection::Ids(vec![1, 4]);
+
+    assert!(ScreenSelection::select(Some(&selection), &screens()).is_err());
+}
+
+#[test]
+fn merged_screens_share_a_single_target() {
+    let targets = assign_screen_targets(&config("multi_screen_mode: merge"), screens()).unwrap();
+
+    assert_eq!(targets.len(), 1);
+    assert_eq!(ids(&targets[0].screens), vec![1, 2, 3]);
+    assert_eq!(targets[0].entity_id, "light.main");
+}
+
+#[test]
+fn separate_screens_submit_to_their_own_targets() {
+    let config = config(
+        "
+multi_screen_mode: separate
+screen_targets:
+  2: light.left
+  3: light.right
+",
+    );
+
+    let targets = assign_screen_targets(&config, screens()).unwrap();
+
+    assert_eq!(
+        targets
+            .iter()
+            .map(|target| (ids(&target.screens), target.entity_id.as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            (vec![1], "light.main"),
+            (vec![2], "light.left"),
+            (vec![3], "light.right"),
+        ]
+    );
+}
+
+#[test]
+fn separate_screens_must_not_share_a_target() {
+    let config = config(
+        "
+multi_screen_mode: separate
+screen_targets:
+  2: light.left
+",
+    );
+
+    assert!(assign_screen_targets(&config, screens()).is_err());
+}
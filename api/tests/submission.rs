@@ -0,0 +1,74 @@
+use desktop_dye_api::{
+    functions::{decide_submission, max_color_difference, SubmissionDecision},
+    models::colors::RgbColor,
+};
+
+const THRESHOLD: f64 = 1.0;
+
+#[test]
+fn first_colors_are_sent() {
+    assert_eq!(
+        decide_submission(None, THRESHOLD, 0.0, None),
+        SubmissionDecision::First
+    );
+}
+
+#[test]
+fn colors_that_changed_beyond_the_threshold_are_sent() {
+    let last = [RgbColor::new(200, 40, 40)];
+    let current = [RgbColor::new(40, 40, 200)];
+    let change = max_color_difference(&current, &last);
+
+    let decision = decide_submission(Some(change), THRESHOLD, 0.0, Some(60.0));
+
+    assert_eq!(decision, SubmissionDecision::Changed);
+    assert!(decision.should_submit());
+}
+
+#[test]
+fn colors_within_the_threshold_are_skipped() {
+    let last = [RgbColor::new(200, 40, 40)];
+    let current = [RgbColor::new(201, 40, 40)];
+    let change = max_color_difference(&current, &last);
+    assert!(change > 0.0 && change <= THRESHOLD);
+
+    let decision = decide_submission(Some(change), THRESHOLD, 10.0, Some(60.0));
+
+    assert_eq!(decision, SubmissionDecision::Skip);
+    assert!(!decision.should_submit());
+    assert_eq!(
+        decide_submission(Some(THRESHOLD), THRESHOLD, 10.0, None),
+        SubmissionDecision::Skip
+    );
+}
+
+#[test]
+fn palettes_of_different_sizes_are_sent() {
+    let last = [RgbColor::new(200, 40, 40)];
+    let current = [RgbColor::new(200, 40, 40), RgbColor::new(40, 40, 200)];
+    let change = max_color_difference(&current, &last);
+
+    assert_eq!(change, f64::INFINITY);
+    assert_eq!(
+        decide_submission(Some(change), THRESHOLD, 0.0, None),
+        SubmissionDecision::Changed
+    );
+}
+
+#[test]
+fn unchanged_colors_are_sent_again_after_the_max_age() {
+    assert_eq!(
+        decide_submission(Some(0.0), THRESHOLD, 59.9, Some(60.0)),
+        SubmissionDecision::Skip
+    );
+    assert_eq!(
+        decide_submission(Some(0.0), THRESHOLD, 60.0, Some(60.0)),
+        SubmissionDecision::Expired
+    );
+    assert!(decide_submission(Some(0.0), THRESHOLD, 60.0, Some(60.0)).should_submit());
+    // Without a max age, unchanged colors are never sent again.
+    assert_eq!(
+        decide_submission(Some(0.0), THRESHOLD, 3600.0, None),
+        SubmissionDecision::Skip
+    );
+}
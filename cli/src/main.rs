@@ -3,14 +3,24 @@ mod progress;
 use anyhow::*;
 use colored::Colorize;
 use desktop_dye_api::{
-    config::DesktopDyeConfig,
-    functions::{get_colors_from_screen, ToHexValue, ToRgb},
+    config::{
+        CaptureIntervalMode, ColorSelectionMode, DesktopDyeConfig, MultiScreenMode,
+        PixelSourceKind, ScreenSelection,
+    },
+    functions::{
+        assign_screen_targets, calculate_led_colors, capture_frame, decide_submission,
+        get_colors_from_frame, max_color_difference, AdaptiveInterval, ColorSmoother,
+        CombinedPixelSource, FrameFingerprint, ImagePixelSource, LetterboxDetector,
+        PaletteExtractorRegistry, PixelSource, ScreenPixelSource, SubmissionDecision, ToHexValue,
+        ToRgb,
+    },
     models::colors::{
-        DesktopDyePayload, DisplayForColorFormat, HomeAssistantHsbColor, HomeAssistantRgbColor,
-        HomeAssistantRgbbColor, HsvColor,
+        DesktopDyePayload, DisplayForColorFormat, DominantColor, HomeAssistantHsbColor,
+        HomeAssistantRgbColor, HomeAssistantRgbbColor, HsvColor, RgbColor,
     },
+    models::frame::Frame,
 };
-use home_assistant_api::{HomeAssistantApi, HomeAssistantConfig};
+use home_assistant_api::{DataMap, HomeAssistantApi, HomeAssistantConfig};
 use prisma::{Lerp, Rgb};
 use progress::Progress;
 use rand::Rng;
@@ -18,6 +28,122 @@ use screenshots::Screen;
 
 const MAX_FAILURES: u8 = 3;
 
+/// The colors calculated from a single capture.
+#[derive(PartialEq)]
+struct CapturedColors {
+    colors: Vec<DominantColor<HsvColor>>,
+    led_colors: Option<Vec<RgbColor>>,
+}
+
+impl CapturedColors {
+    /// Returns how different these colors look from the given colors, as the largest CIEDE2000
+    /// difference between any of their colors or LED colors.
+    fn difference(&self, other: &CapturedColors) -> f64 {
+        let colors = |captured: &CapturedColors| {
+            captured
+                .colors
+                .iter()
+                .map(|dominant_color| dominant_color.color)
+                .collect::<Vec<_>>()
+        };
+        let led_colors =
+            |captured: &CapturedColors| captured.led_colors.clone().unwrap_or_default();
+
+        max_color_difference(&colors(self), &colors(other))
+            .max(max_color_difference(&led_colors(self), &led_colors(other)))
+    }
+}
+
+/// A source of frames and the Home Assistant entity its colors are submitted to, along with the
+/// state kept between captures.
+struct CaptureTarget {
+    source: Box<dyn PixelSource>,
+    entity_id: String,
+    letterbox_detector: Option<LetterboxDetector>,
+    last_colors: Option<CapturedColors>,
+    /// The fingerprint of the last frame whose colors were calculated, whether or not they were
+    /// submitted. Colors that weren't submitted are within the color change threshold of
+    /// `last_colors`, so frames that haven't changed since don't need to be calculated again.
+    last_fingerprint: Option<FrameFingerprint>,
+    /// The fingerprint of the last captured frame.
+    previous_fingerprint: Option<FrameFingerprint>,
+    last_calculation_time: std::time::Duration,
+    saved_calculation_time: std::time::Duration,
+    /// Moves the submitted colors towards the calculated colors, if smoothing is enabled.
+    smoother: Option<ColorSmoother>,
+    /// When the smoother was last advanced.
+    last_smoothing_time: std::time::Instant,
+    /// When `last_colors` were submitted.
+    last_submission_time: std::time::Instant,
+}
+
+impl CaptureTarget {
+    fn new(config: &DesktopDyeConfig, source: Box<dyn PixelSource>, entity_id: String) -> Self {
+        Self {
+            source,
+            entity_id,
+            letterbox_detector: config.detect_letterbox.then(LetterboxDetector::default),
+            last_colors: None,
+            last_fingerprint: None,
+            previous_fingerprint: None,
+            last_calculation_time: std::time::Duration::ZERO,
+            saved_calculation_time: std::time::Duration::ZERO,
+            smoother: config.smoothing.clone().map(ColorSmoother::new),
+            last_smoothing_time: std::time::Instant::now(),
+            last_submission_time: std::time::Instant::now(),
+        }
+    }
+
+    /// Decides whether colors that changed by `change` since the last submission (`None` if
+    /// nothing was submitted yet) should be submitted.
+    fn submission_decision(
+        &self,
+        config: &DesktopDyeConfig,
+        change: Option<f64>,
+    ) -> SubmissionDecision {
+        decide_submission(
+            change,
+            config.color_change_threshold,
+            self.last_submission_time.elapsed().as_secs_f64(),
+            config.max_submission_age,
+        )
+    }
+
+    /// Indicates whether the submitted colors are still moving towards the calculated colors.
+    fn is_transitioning(&self) -> bool {
+        self.smoother
+            .as_ref()
+            .map(|smoother| !smoother.is_settled())
+            .unwrap_or(false)
+    }
+
+    /// Moves the submitted colors towards the given calculated colors (or the last calculated
+    /// colors, if none are given), and returns the colors to submit.
+    fn smooth_colors(
+        &mut self,
+        colors: Option<Vec<DominantColor<HsvColor>>>,
+    ) -> Vec<DominantColor<HsvColor>> {
+        let now = std::time::Instant::now();
+        let elapsed = now.duration_since(self.last_smoothing_time).as_secs_f64();
+        self.last_smoothing_time = now;
+
+        match (&mut self.smoother, colors) {
+            (Some(smoother), colors) => {
+                if let Some(colors) = &colors {
+                    smoother.set_target(colors);
+                }
+                smoother.advance(elapsed)
+            }
+            (None, Some(colors)) => colors,
+            (None, None) => self
+                .last_colors
+                .as_ref()
+                .map(|last_colors| last_colors.colors.clone())
+                .unwrap_or_default(),
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     print_title();
@@ -40,11 +166,47 @@ async fn main() -> Result<()> {
         DesktopDyeConfig::get().context(format!("Failed to get config at\n  {}", config_path))?;
     p.success();
 
+    let registry =
+        PaletteExtractorRegistry::with_builtin_extractors(&config.kmeans, &config.hue_histogram);
     println!(
-        "Color selection mode set to {}",
-        config.mode.to_string().italic()
+        "Using the {} algorithm",
+        config.algorithm.to_string().italic()
     );
 
+    if let Some(edge_zones) = &config.edge_zones {
+        println!(
+            "Edge zone mode enabled, sending one color for each of the {} zones",
+            edge_zones.count().to_string().italic()
+        );
+    } else {
+        println!(
+            "Color selection mode set to {}",
+            config.mode.to_string().italic()
+        );
+
+        if config.mode == ColorSelectionMode::Gradient {
+            println!(
+                "Sending a gradient of {} colors, interpolated in {}",
+                config.gradient.count.to_string().italic(),
+                config.gradient.color_space.to_string().italic()
+            );
+        }
+    }
+
+    if let Some(smoothing) = &config.smoothing {
+        println!(
+            "Smoothing colors ({}) with a time constant of {} second(s)",
+            smoothing.method.to_string().italic(),
+            smoothing.time_constant
+        );
+        if let Some(send_rate) = smoothing.send_rate {
+            println!(
+                "Sending intermediate colors {} time(s) per second between captures",
+                send_rate
+            );
+        }
+    }
+
     let mut p = Progress::new("Checking Home Assistant connection");
     let api = HomeAssistantApi::new(&HomeAssistantConfig::new(
         config.ha_endpoint.clone(),
@@ -60,54 +222,49 @@ async fn main() -> Result<()> {
     }
     p.success();
 
-    let mut p = Progress::new("Checking for screens");
-    let screens = Screen::all().unwrap();
-    if screens.is_empty() {
-        p.fail();
-        return Err(anyhow!(
-            "No screens found. Please check your config file at\n  {}",
-            config_path
-        ));
-    }
-    p.success();
-    println!("Found {} screens:", &screens.len());
-    for screen in &screens {
-        print!(
-            "  - id: {} ({}x{})",
-            screen.display_info.id, screen.display_info.width, screen.display_info.height
-        );
-        if screen.display_info.is_primary {
-            print!(" (primary)");
+    let mut targets = match config.source {
+        PixelSourceKind::Screen => {
+            let screens = select_screens(&config, &config_path)?;
+            get_screen_targets(&config, screens)?
         }
-        println!();
-    }
-
-    let target_screen: &Screen;
-
-    if let Some(screen_id) = config.screen_id {
-        target_screen = screens
-            .iter()
-            .find(|screen| screen.display_info.id == screen_id)
-            .ok_or_else(|| anyhow!("Failed to find screen with id {}", screen_id))?;
-        println!("Using screen with id {} (from config)", screen_id);
-    } else {
-        target_screen = screens
-            .iter()
-            .find(|screen| screen.display_info.is_primary)
-            .ok_or_else(|| anyhow!("Failed to find primary screen"))?;
-        println!(
-            "Using screen with id {} (the primary screen)",
-            target_screen.display_info.id
-        );
-    }
+        PixelSourceKind::Image => {
+            let image_path = config.image_path.as_ref().unwrap();
+            let source = ImagePixelSource::new(image_path)?;
+            println!("Using {} (from config)", source.description());
+            vec![CaptureTarget::new(
+                &config,
+                Box::new(source),
+                config.ha_target_entity_id.clone(),
+            )]
+        }
+    };
 
     let mut failures = 0;
     let mut last_submission_time: std::time::Instant;
-    let mut last_colors: Option<Vec<HsvColor>> = None;
+    let mut adaptive_interval = match config.capture_interval_mode {
+        CaptureIntervalMode::Fixed => None,
+        CaptureIntervalMode::Adaptive => {
+            println!(
+                "Adaptive capture interval enabled, capturing every {} to {} second(s) depending on screen activity",
+                config.min_capture_interval, config.max_capture_interval
+            );
+            Some(AdaptiveInterval::from_config(&config))
+        }
+    };
 
     loop {
         last_submission_time = std::time::Instant::now();
-        let res = capture_and_submit(&api, &config, target_screen, last_colors.as_ref()).await;
+        let mut res = Ok(());
+        let mut difference: f64 = 0.0;
+        for target in &mut targets {
+            match capture_and_submit(&api, &config, &registry, target).await {
+                Result::Ok(target_difference) => difference = difference.max(target_difference),
+                Err(e) => {
+                    res = Err(e);
+                    break;
+                }
+            }
+        }
         if let Err(e) = res {
             failures += 1;
             if failures >= MAX_FAILURES {
@@ -118,67 +275,199 @@ async fn main() -> Result<()> {
             println!("Retrying in 5 seconds...");
             tokio::time::sleep(std::time::Duration::from_secs(5)).await;
         } else {
-            last_colors = Some(res.unwrap());
             failures = 0;
 
-            let seconds_remaining =
-                config.capture_interval - last_submission_time.elapsed().as_secs_f64();
+            let capture_interval = match &mut adaptive_interval {
+                Some(adaptive_interval) => adaptive_interval.update(difference),
+                None => config.capture_interval,
+            };
+
+            let seconds_remaining = capture_interval - last_submission_time.elapsed().as_secs_f64();
             if seconds_remaining > 0.0 {
                 println!(
                     "Waiting {} second(s) for next capture ({:.2}s remaining)...",
-                    config.capture_interval, seconds_remaining
+                    (capture_interval * 100.0).round() / 100.0,
+                    seconds_remaining
                 );
-                tokio::time::sleep(std::time::Duration::from_secs_f64(seconds_remaining)).await;
+                let next_capture_time = std::time::Instant::now()
+                    + std::time::Duration::from_secs_f64(seconds_remaining);
+
+                match config
+                    .smoothing
+                    .as_ref()
+                    .and_then(|smoothing| smoothing.send_rate)
+                {
+                    Some(send_rate) => {
+                        send_transition_frames(
+                            &api,
+                            &config,
+                            &mut targets,
+                            send_rate,
+                            next_capture_time,
+                        )
+                        .await
+                    }
+                    None => tokio::time::sleep_until(next_capture_time.into()).await,
+                }
             }
         }
     }
 }
 
+/// Captures the colors of the given target and submits them to Home Assistant if they changed.
+///
+/// Returns how different the captured frame is from the previous capture (from 0 to 1).
 async fn capture_and_submit(
     api: &HomeAssistantApi,
     config: &DesktopDyeConfig,
-    screen: &Screen,
-    last_colors: Option<&Vec<HsvColor>>,
-) -> Result<Vec<HsvColor>> {
-    let mut p = Progress::new("Getting colors from screen");
-    let colors_res = get_colors_from_screen(config, screen).await;
-    if let Err(e) = colors_res {
+    registry: &PaletteExtractorRegistry,
+    target: &mut CaptureTarget,
+) -> Result<f64> {
+    let mut p =
+        Progress::new(format!("Getting colors from {}", target.source.description()).as_str());
+    let frame_res = capture_target_frame(
+        config,
+        target.source.as_mut(),
+        target.letterbox_detector.as_mut(),
+    );
+    if let Err(e) = frame_res {
+        p.fail();
+        return Err(e);
+    }
+    let frame = frame_res.unwrap();
+
+    let fingerprint = FrameFingerprint::new(&frame);
+    let difference = target
+        .previous_fingerprint
+        .as_ref()
+        .map(|previous_fingerprint| fingerprint.difference(previous_fingerprint))
+        .unwrap_or(0.0);
+    target.previous_fingerprint = Some(fingerprint.clone());
+
+    // Static screens result in the same colors, so they don't need to be calculated again.
+    if let Some(last_fingerprint) = &target.last_fingerprint {
+        let change = fingerprint.difference(last_fingerprint);
+        if change <= config.frame_change_threshold {
+            p.success();
+            target.saved_calculation_time += target.last_calculation_time;
+            println!(
+                "Capture hasn't changed ({:.2}% different, threshold is {:.2}%), skipping color calculation (saved ~{} ms, {:.1}s in total)",
+                change * 100.0,
+                config.frame_change_threshold * 100.0,
+                target.last_calculation_time.as_millis(),
+                target.saved_calculation_time.as_secs_f64()
+            );
+
+            // The submitted colors keep moving towards the last calculated colors until they
+            // reach them.
+            if target.is_transitioning() {
+                submit_transition_frame(api, config, target).await?;
+            } else {
+                target.last_smoothing_time = std::time::Instant::now();
+            }
+
+            if target.submission_decision(config, Some(0.0)) == SubmissionDecision::Expired {
+                if let Some(last_colors) = &target.last_colors {
+                    println!(
+                        "Last submission is {:.1}s old (max age is {}s), submitting colors again",
+                        target.last_submission_time.elapsed().as_secs_f64(),
+                        config.max_submission_age.unwrap_or_default()
+                    );
+                    submit_colors(api, config, &target.entity_id, last_colors, true).await?;
+                    target.last_submission_time = std::time::Instant::now();
+                }
+            }
+
+            return Ok(difference);
+        }
+    }
+
+    let calculation_start = std::time::Instant::now();
+    let captured_res = calculate_colors(config, registry, &frame).await;
+    if let Err(e) = captured_res {
         p.fail();
         return Err(e);
     }
     p.success();
-    let colors = colors_res.unwrap();
+    target.last_calculation_time = calculation_start.elapsed();
+    let captured = captured_res.unwrap();
+    let captured = CapturedColors {
+        colors: target.smooth_colors(Some(captured.colors)),
+        led_colors: captured.led_colors,
+    };
 
-    if let Some(last_colors) = last_colors {
-        if last_colors == &colors {
-            println!("Colors haven't changed, skipping submission");
-            return Ok(colors);
+    let change = target
+        .last_colors
+        .as_ref()
+        .map(|last_colors| captured.difference(last_colors));
+    match (target.submission_decision(config, change), change) {
+        (SubmissionDecision::Changed, Some(change)) => println!(
+            "Colors changed by {:.2} (threshold is {:.2}), submitting colors",
+            change, config.color_change_threshold
+        ),
+        (SubmissionDecision::Expired, Some(change)) => println!(
+            "Colors changed by {:.2} (threshold is {:.2}), but the last submission is {:.1}s old (max age is {}s), submitting colors again",
+            change,
+            config.color_change_threshold,
+            target.last_submission_time.elapsed().as_secs_f64(),
+            config.max_submission_age.unwrap_or_default()
+        ),
+        (SubmissionDecision::Skip, Some(change)) => {
+            target.last_fingerprint = Some(fingerprint);
+            println!(
+                "Colors changed by {:.2} (threshold is {:.2}), skipping submission",
+                change, config.color_change_threshold
+            );
+            return Ok(difference);
         }
+        _ => {}
     }
 
-    for (i, color) in colors.iter().enumerate() {
-        let rgb_color = color.to_rgb();
-        print!("  {}. ", i + 1);
+    submit_colors(api, config, &target.entity_id, &captured, true).await?;
+    target.last_colors = Some(captured);
+    target.last_submission_time = std::time::Instant::now();
+    target.last_fingerprint = Some(fingerprint);
 
-        let color_string = format!(
-            "#{} ({})",
-            color.to_hex_value(),
-            color.display_for_color_format(&config.color_format)
-        );
+    Ok(difference)
+}
 
-        println!(
-            "{}",
-            color_string
-                .bold()
-                .white()
-                .on_truecolor(rgb_color.red(), rgb_color.green(), rgb_color.blue())
-                .to_string()
-        );
+/// Submits the given colors to the given Home Assistant entity.
+///
+/// When `verbose` is set, the colors and their payload are printed as well.
+async fn submit_colors(
+    api: &HomeAssistantApi,
+    config: &DesktopDyeConfig,
+    entity_id: &str,
+    captured: &CapturedColors,
+    verbose: bool,
+) -> Result<()> {
+    let colors = &captured.colors;
+    if verbose {
+        for (i, DominantColor { color, weight }) in colors.iter().enumerate() {
+            let rgb_color = color.to_rgb();
+            print!("  {}. ", i + 1);
+
+            let color_string = format!(
+                "#{} ({}) {:.1}%",
+                color.to_hex_value(),
+                color.display_for_color_format(&config.color_format),
+                weight * 100.0
+            );
+
+            println!(
+                "{}",
+                color_string.bold().white().on_truecolor(
+                    rgb_color.red(),
+                    rgb_color.green(),
+                    rgb_color.blue()
+                )
+            );
+        }
     }
 
     let colors_payload = colors
-        .clone()
-        .into_iter()
+        .iter()
+        .map(|dominant_color| dominant_color.color)
         .map::<Box<dyn DesktopDyePayload>, _>(|color| match config.color_format {
             desktop_dye_api::config::ColorFormat::Hsb => {
                 Box::new(HomeAssistantHsbColor::from(color))
@@ -194,30 +483,245 @@ async fn capture_and_submit(
         .collect::<Vec<_>>()
         .join(" ");
 
-    println!(
-        "Sending colors value ({}): \"{}\"",
-        config.color_format, colors_payload
-    );
+    if verbose {
+        println!(
+            "Sending colors value ({}): \"{}\"",
+            config.color_format, colors_payload
+        );
+    }
+
+    // The weights are rounded, as lights can't make use of more precise proportions anyway.
+    let mut attributes = DataMap::from([(
+        "color_weights".to_string(),
+        colors
+            .iter()
+            .map(|dominant_color| (dominant_color.weight * 1000.0).round() / 1000.0)
+            .collect::<Vec<_>>()
+            .into(),
+    )]);
 
-    let mut p = Progress::new("Submitting colors to Home Assistant");
+    if let Some(led_colors) = &captured.led_colors {
+        if verbose {
+            println!(
+                "Sending {} LED colors (as the 'led_colors' attribute)",
+                led_colors.len()
+            );
+        }
+
+        attributes.insert(
+            "led_colors".to_string(),
+            led_colors
+                .iter()
+                .map(|color| vec![color.red(), color.green(), color.blue()])
+                .collect::<Vec<_>>()
+                .into(),
+        );
+    }
+
+    let mut p = verbose.then(|| {
+        Progress::new(format!("Submitting colors to Home Assistant ({})", entity_id).as_str())
+    });
     let api_res = api
-        .set_state(
-            config.ha_target_entity_id.to_owned(),
-            colors_payload.clone(),
-            None,
-            true,
-        )
+        .set_state(entity_id.to_owned(), colors_payload, Some(attributes), true)
         .await
         .context("Failed to submit colors to Home Assistant");
 
-    if let Err(e) = api_res {
-        p.fail();
-        return Err(e);
+    if let Some(p) = &mut p {
+        match api_res {
+            Result::Ok(_) => p.success(),
+            Err(_) => p.fail(),
+        }
+    }
+
+    api_res
+}
+
+/// Submits the next intermediate colors of the given target, if they are still moving towards
+/// the last calculated colors.
+async fn submit_transition_frame(
+    api: &HomeAssistantApi,
+    config: &DesktopDyeConfig,
+    target: &mut CaptureTarget,
+) -> Result<()> {
+    if !target.is_transitioning() {
+        return Ok(());
+    }
+
+    let captured = CapturedColors {
+        colors: target.smooth_colors(None),
+        led_colors: target
+            .last_colors
+            .as_ref()
+            .and_then(|last_colors| last_colors.led_colors.clone()),
+    };
+    let change = target
+        .last_colors
+        .as_ref()
+        .map(|last_colors| captured.difference(last_colors));
+    if !target.submission_decision(config, change).should_submit() {
+        return Ok(());
+    }
+
+    submit_colors(api, config, &target.entity_id, &captured, false).await?;
+    target.last_colors = Some(captured);
+    target.last_submission_time = std::time::Instant::now();
+
+    Ok(())
+}
+
+/// Submits the intermediate colors of every target at the given rate (per second) until the
+/// given time, so the lights fade between captures.
+async fn send_transition_frames(
+    api: &HomeAssistantApi,
+    config: &DesktopDyeConfig,
+    targets: &mut [CaptureTarget],
+    send_rate: f64,
+    until: std::time::Instant,
+) {
+    let frame_interval = std::time::Duration::from_secs_f64(1.0 / send_rate);
+
+    loop {
+        let next_frame_time = std::time::Instant::now() + frame_interval;
+        if next_frame_time >= until {
+            break;
+        }
+        tokio::time::sleep_until(next_frame_time.into()).await;
+
+        for target in targets.iter_mut() {
+            if let Err(e) = submit_transition_frame(api, config, target).await {
+                // Failures are reported (and retried) by the next capture.
+                println!("Error: {}", e);
+                println!("Stopping transition until next capture");
+                tokio::time::sleep_until(until.into()).await;
+                return;
+            }
+        }
+    }
+
+    tokio::time::sleep_until(until.into()).await;
+}
+
+fn capture_target_frame(
+    config: &DesktopDyeConfig,
+    source: &mut dyn PixelSource,
+    letterbox_detector: Option<&mut LetterboxDetector>,
+) -> Result<Frame> {
+    let mut frame = capture_frame(config, source)?;
+
+    if let Some(letterbox_detector) = letterbox_detector {
+        let last_picture_area = letterbox_detector.picture_area();
+        let picture_area = letterbox_detector.update(&frame);
+
+        if picture_area != last_picture_area {
+            match picture_area {
+                Some(area) => println!(
+                    "Black bars detected, using the picture area of {}x{} at ({}, {})",
+                    area.width, area.height, area.x, area.y
+                ),
+                None => println!("Black bars no longer detected, using the entire frame"),
+            }
+        }
+
+        if let Some(picture_area) = picture_area {
+            frame = frame.crop(&picture_area);
+        }
     }
 
+    Ok(frame)
+}
+
+async fn calculate_colors(
+    config: &DesktopDyeConfig,
+    registry: &PaletteExtractorRegistry,
+    frame: &Frame,
+) -> Result<CapturedColors> {
+    let colors = get_colors_from_frame(config, registry, frame).await?;
+    let led_colors = config
+        .led_layout
+        .as_ref()
+        .map(|led_layout| calculate_led_colors(frame, led_layout));
+
+    Ok(CapturedColors { colors, led_colors })
+}
+
+fn select_screens(config: &DesktopDyeConfig, config_path: &str) -> Result<Vec<Screen>> {
+    let mut p = Progress::new("Checking for screens");
+    let screens = Screen::all().unwrap();
+    if screens.is_empty() {
+        p.fail();
+        return Err(anyhow!(
+            "No screens found. Please check your config file at\n  {}",
+            config_path
+        ));
+    }
     p.success();
+    println!("Found {} screens:", &screens.len());
+    for screen in &screens {
+        print!(
+            "  - id: {} ({}x{})",
+            screen.display_info.id, screen.display_info.width, screen.display_info.height
+        );
+        if screen.display_info.is_primary {
+            print!(" (primary)");
+        }
+        println!();
+    }
+
+    let target_screens = ScreenSelection::select(config.screen_id.as_ref(), &screens)?;
 
-    Ok(colors)
+    match &config.screen_id {
+        Some(ScreenSelection::All) => println!("Using all screens (from config)"),
+        Some(screen_id) => println!("Using screen(s) with id {} (from config)", screen_id),
+        None => println!(
+            "Using screen with id {} (the primary screen)",
+            target_screens[0].display_info.id
+        ),
+    }
+
+    Ok(target_screens)
+}
+
+/// Creates the capture targets for the given screens.
+///
+/// When multiple screens are merged, they share a single target. Otherwise, every screen gets
+/// its own target, which submits to the entity set for it in `screen_targets`.
+fn get_screen_targets(
+    config: &DesktopDyeConfig,
+    screens: Vec<Screen>,
+) -> Result<Vec<CaptureTarget>> {
+    let is_separate = config.multi_screen_mode == MultiScreenMode::Separate;
+
+    Ok(assign_screen_targets(config, screens)?
+        .into_iter()
+        .map(|target| {
+            let source: Box<dyn PixelSource> = if target.screens.len() == 1 {
+                if is_separate {
+                    println!(
+                        "Sending the colors of screen {} to {}",
+                        target.screens[0].display_info.id,
+                        target.entity_id.italic()
+                    );
+                }
+                Box::new(ScreenPixelSource::new(target.screens[0]))
+            } else {
+                println!(
+                    "Merging the colors of {} screens into a single palette",
+                    target.screens.len()
+                );
+                Box::new(CombinedPixelSource::new(
+                    target
+                        .screens
+                        .into_iter()
+                        .map::<Box<dyn PixelSource>, _>(|screen| {
+                            Box::new(ScreenPixelSource::new(screen))
+                        })
+                        .collect(),
+                ))
+            };
+
+            CaptureTarget::new(config, source, target.entity_id)
+        })
+        .collect())
 }
 
 fn print_title() {
@@ -240,7 +744,7 @@ fn print_title() {
 
     let colored_package_name = chars
         .into_iter()
-        .zip(colors.into_iter())
+        .zip(colors)
         .map(|(c, color)| {
             c.to_string()
                 .on_truecolor(color.red(), color.green(), color.blue())
@@ -105,7 +105,7 @@ impl HomeAssistantApi {
         if let Some(attributes) = attributes {
             data.insert(
                 "attributes".to_string(),
-                serde_json::Map::from_iter(attributes.into_iter()).into(),
+                serde_json::Map::from_iter(attributes).into(),
             );
         }
 